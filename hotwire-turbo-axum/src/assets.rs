@@ -0,0 +1,165 @@
+//! Serves embedded [`Assets`] under their digested file names.
+//!
+//! Digested names change with the content, so responses are cached for a year as `immutable`.
+//!
+//! ```
+//! use axum::Router;
+//! use hotwire_turbo::assets::{Asset, Assets};
+//!
+//! let assets = Assets::new("/assets")
+//!     .asset(Asset::new("@hotwired/turbo", "turbo.js", b"/* turbo */".as_slice()));
+//!
+//! let app: Router = Router::new().merge(hotwire_turbo_axum::assets::router(assets));
+//! ```
+use axum::body::Bytes;
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use hotwire_turbo::assets::{Asset, Assets};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// A router serving every asset at `<prefix>/<digested name>`.
+pub fn router<S>(assets: Assets) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let path = format!("{}/:name", assets.prefix());
+    let served: Arc<HashMap<String, Served>> = Arc::new(
+        assets
+            .iter()
+            .map(|asset| (asset.digested_name(), Served::new(asset.clone())))
+            .collect(),
+    );
+    Router::new().route(
+        &path,
+        get(
+            move |Path(name): Path<String>, headers: HeaderMap| async move {
+                match served.get(&name) {
+                    Some(served) => served.response(&headers),
+                    None => StatusCode::NOT_FOUND.into_response(),
+                }
+            },
+        ),
+    )
+}
+
+/// An asset with its response body and headers prepared once, rather than per request.
+struct Served {
+    content: Bytes,
+    content_type: &'static str,
+    etag: String,
+}
+
+impl Served {
+    fn new(asset: Asset) -> Self {
+        let content_type = asset.content_type();
+        let etag = format!(r#""{}""#, asset.digest());
+        let content = match asset.into_content() {
+            Cow::Borrowed(content) => Bytes::from_static(content),
+            Cow::Owned(content) => Bytes::from(content),
+        };
+        Served {
+            content,
+            content_type,
+            etag,
+        }
+    }
+
+    fn response(&self, headers: &HeaderMap) -> Response {
+        let cache_headers = [
+            (
+                header::CACHE_CONTROL,
+                HeaderValue::from_static(CACHE_CONTROL),
+            ),
+            (
+                header::ETAG,
+                HeaderValue::from_str(&self.etag).expect("hex digest"),
+            ),
+        ];
+        if crate::etag_matches(headers, &self.etag) {
+            return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+        }
+        (
+            cache_headers,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(self.content_type),
+            )],
+            // Cloning `Bytes` only bumps a reference count.
+            self.content.clone(),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        router(Assets::new("/assets").asset(turbo()))
+    }
+
+    fn turbo() -> Asset {
+        Asset::new("@hotwired/turbo", "turbo.js", b"/* turbo */".as_slice())
+    }
+
+    fn request(uri: &str) -> axum::http::request::Builder {
+        Request::builder().uri(uri)
+    }
+
+    #[tokio::test]
+    async fn serves_digested_name() {
+        let uri = format!("/assets/{}", turbo().digested_name());
+
+        let res = app()
+            .oneshot(request(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!("text/javascript", res.headers()["Content-Type"]);
+        assert_eq!(CACHE_CONTROL, res.headers()["Cache-Control"]);
+        assert_eq!(
+            format!(r#""{}""#, turbo().digest()),
+            res.headers()["ETag"].to_str().unwrap()
+        );
+        assert_eq!("/* turbo */", crate::tests::body_text(res).await);
+    }
+
+    #[tokio::test]
+    async fn not_modified() {
+        let uri = format!("/assets/{}", turbo().digested_name());
+
+        let res = app()
+            .oneshot(
+                request(&uri)
+                    .header("If-None-Match", format!(r#""{}""#, turbo().digest()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::NOT_MODIFIED, res.status());
+        assert_eq!("", crate::tests::body_text(res).await);
+    }
+
+    #[tokio::test]
+    async fn undigested_name_is_not_found() {
+        let res = app()
+            .oneshot(request("/assets/turbo.js").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::NOT_FOUND, res.status());
+    }
+}
@@ -0,0 +1,202 @@
+//! Serves [TurboBoost Commands](https://github.com/hopsoft/turbo_boost-commands) from one route.
+//!
+//! ```
+//! use axum::Router;
+//! use hotwire_turbo::command::Commands;
+//! use hotwire_turbo::stream;
+//!
+//! let commands = Commands::new().command("FlashCommand#dismiss", |request| {
+//!     Ok(stream::remove(request.element_id().unwrap_or("flash")))
+//! });
+//!
+//! # fn session_csrf_token(headers: &axum::http::HeaderMap) -> Option<String> { None }
+//! let app: Router = Router::new().merge(hotwire_turbo_axum::command::router(
+//!     commands,
+//!     |headers, request| {
+//!         session_csrf_token(headers).is_some_and(|token| request.csrf_token() == Some(&token))
+//!     },
+//! ));
+//! ```
+//!
+//! The [`Command`] extractor and [`CommandResponse`] responder can also be used in a handler of
+//! your own, e.g. to dispatch with access to application state.
+use crate::TurboStream;
+use axum::async_trait;
+use axum::extract::{FromRequest, Request};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use hotwire_turbo::command::{self, Commands, Error};
+use std::sync::Arc;
+
+/// Extracts the command invocation from the JSON request body.
+///
+/// Rejects invalid bodies with `400 Bad Request`.
+#[derive(Clone, Debug)]
+pub struct Command(pub command::Request);
+
+#[async_trait]
+impl<S> FromRequest<S> for Command
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let body = String::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        command::Request::from_json(&body)
+            .map(Command)
+            .map_err(|error| CommandResponse(Err(error)).into_response())
+    }
+}
+
+/// Responds with the stream actions of a dispatched command, or the status matching its error.
+///
+/// The message of a failed handler is logged rather than sent to the client.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct CommandResponse(pub Result<String, Error>);
+
+impl IntoResponse for CommandResponse {
+    fn into_response(self) -> Response {
+        match self.0 {
+            Ok(body) => TurboStream(body).into_response(),
+            Err(Error::Failed(reason)) => {
+                tracing::error!(%reason, "command failed");
+                (StatusCode::INTERNAL_SERVER_ERROR, "command failed").into_response()
+            }
+            Err(error) => {
+                let status = match error {
+                    Error::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+                    Error::UnknownCommand(_) => StatusCode::NOT_FOUND,
+                    Error::InvalidToken => StatusCode::FORBIDDEN,
+                    Error::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, error.to_string()).into_response()
+            }
+        }
+    }
+}
+
+impl From<Result<String, Error>> for CommandResponse {
+    fn from(result: Result<String, Error>) -> Self {
+        Self(result)
+    }
+}
+
+/// A router dispatching every invocation posted to [`command::PATH`] to `commands`.
+///
+/// `verify` checks the CSRF token of every invocation, e.g. against the one stored in the
+/// session the request cookies point to. Invocations it rejects get `403 Forbidden`.
+pub fn router<S, V>(commands: Commands, verify: V) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    V: Fn(&HeaderMap, &command::Request) -> bool + Send + Sync + 'static,
+{
+    let commands = Arc::new(commands);
+    let verify = Arc::new(verify);
+    Router::new().route(
+        command::PATH,
+        post(
+            move |headers: HeaderMap, Command(request): Command| async move {
+                if !verify(&headers, &request) {
+                    return CommandResponse(Err(Error::InvalidToken));
+                }
+                CommandResponse(commands.dispatch(&request))
+            },
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use hotwire_turbo::stream;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let commands = Commands::new()
+            .command("FlashCommand#dismiss", |request| {
+                Ok(stream::remove(request.element_id().unwrap_or_default()))
+            })
+            .command("FlashCommand#fail", |_| {
+                Err(Error::Failed("database password rejected".to_string()))
+            });
+        router(commands, |_, request| request.csrf_token() == Some("token"))
+    }
+
+    fn invocation(body: &str) -> axum::http::Request<Body> {
+        axum::http::Request::builder()
+            .method("POST")
+            .uri(command::PATH)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dispatches_command() {
+        let res = app()
+            .oneshot(invocation(
+                r#"{"id":"1","name":"FlashCommand#dismiss","driver":"method","elementId":"flash","csrfToken":"token"}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!("text/vnd.turbo-stream.html", res.headers()["Content-Type"]);
+        assert_eq!(stream::remove("flash"), crate::tests::body_text(res).await);
+    }
+
+    #[tokio::test]
+    async fn unknown_command() {
+        let res = app()
+            .oneshot(invocation(
+                r#"{"id":"1","name":"FlashCommand#show","driver":"method","csrfToken":"token"}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::NOT_FOUND, res.status());
+        assert_eq!(
+            "unknown command `FlashCommand#show`",
+            crate::tests::body_text(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_csrf_token() {
+        for body in [
+            r#"{"id":"1","name":"FlashCommand#dismiss","driver":"method","csrfToken":"other"}"#,
+            r#"{"id":"1","name":"FlashCommand#dismiss","driver":"method"}"#,
+        ] {
+            let res = app().oneshot(invocation(body)).await.unwrap();
+
+            assert_eq!(StatusCode::FORBIDDEN, res.status());
+        }
+    }
+
+    #[tokio::test]
+    async fn failure_is_not_disclosed() {
+        let res = app()
+            .oneshot(invocation(
+                r#"{"id":"1","name":"FlashCommand#fail","driver":"method","csrfToken":"token"}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+        assert_eq!("command failed", crate::tests::body_text(res).await);
+    }
+
+    #[tokio::test]
+    async fn invalid_body() {
+        let res = app().oneshot(invocation("{}")).await.unwrap();
+
+        assert_eq!(StatusCode::BAD_REQUEST, res.status());
+    }
+}
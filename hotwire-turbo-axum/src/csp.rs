@@ -0,0 +1,240 @@
+//! A per-request Content Security Policy nonce.
+//!
+//! [`CspLayer`] generates a [`Nonce`] for each request and sets the `Content-Security-Policy`
+//! header of the response. Handlers read the nonce with the [`CspNonce`] extractor to stamp
+//! their markup and render the `csp-nonce` meta tag.
+//!
+//! ```
+//! use axum::{routing::get, Router};
+//! use hotwire_turbo::stream;
+//! use hotwire_turbo_axum::csp::{CspLayer, CspNonce};
+//! use hotwire_turbo_axum::TurboStream;
+//!
+//! async fn flash(CspNonce(nonce): CspNonce) -> TurboStream<String> {
+//!     TurboStream(stream::append("flashes", nonce.stamp("<script>flash()</script>")))
+//! }
+//!
+//! let app: Router = Router::new()
+//!     .route("/flash", get(flash))
+//!     .layer(CspLayer::default());
+//! ```
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Request};
+use axum::http::header::InvalidHeaderValue;
+use axum::http::request::Parts;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::Response;
+use hotwire_turbo::csp::Nonce;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+const PLACEHOLDER: &str = "{nonce}";
+
+/// Generates a nonce per request and sets the `Content-Security-Policy` header.
+///
+/// The policy is a template in which every `{nonce}` is replaced by the request's nonce. A
+/// header already set by the handler is left as is.
+///
+/// ```
+/// use hotwire_turbo_axum::csp::CspLayer;
+///
+/// assert!(CspLayer::new("script-src 'nonce-{nonce}'").is_ok());
+/// assert!(CspLayer::new("script-src 'self'\n").is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CspLayer {
+    policy: Arc<str>,
+}
+
+impl CspLayer {
+    /// Fails when the policy isn't a valid header value. Nonces are hexadecimal, so a valid
+    /// template stays valid once they are inserted.
+    pub fn new(policy: &str) -> Result<Self, InvalidHeaderValue> {
+        HeaderValue::from_str(policy)?;
+        Ok(CspLayer {
+            policy: policy.into(),
+        })
+    }
+}
+
+impl Default for CspLayer {
+    /// Allows same origin scripts and styles, and inline ones carrying the nonce.
+    fn default() -> Self {
+        CspLayer::new("script-src 'self' 'nonce-{nonce}'; style-src 'self' 'nonce-{nonce}'")
+            .expect("valid default policy")
+    }
+}
+
+impl<S> Layer<S> for CspLayer {
+    type Service = CspService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CspService {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+/// The service created by [`CspLayer`].
+#[derive(Clone, Debug)]
+pub struct CspService<S> {
+    inner: S,
+    policy: Arc<str>,
+}
+
+impl<S> Service<Request> for CspService<S>
+where
+    S: Service<Request, Response = Response>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let nonce = generate();
+        let policy = HeaderValue::from_str(&self.policy.replace(PLACEHOLDER, nonce.as_str()))
+            .expect("policy validated by CspLayer::new with a hexadecimal nonce");
+        request.extensions_mut().insert(nonce);
+        let future = self.inner.call(request);
+        Box::pin(async move {
+            let mut response = future.await?;
+            response
+                .headers_mut()
+                .entry(header::CONTENT_SECURITY_POLICY)
+                .or_insert(policy);
+            Ok(response)
+        })
+    }
+}
+
+/// Extracts the nonce generated by [`CspLayer`].
+///
+/// Rejects the request with `500 Internal Server Error` when the layer is missing.
+#[derive(Clone, Debug)]
+pub struct CspNonce(pub Nonce);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CspNonce
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Nonce>()
+            .cloned()
+            .map(CspNonce)
+            .ok_or((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Missing CSP nonce, is `CspLayer` installed?",
+            ))
+    }
+}
+
+/// 128 random bits, hex encoded.
+fn generate() -> Nonce {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("operating system random number generator");
+    Nonce::new(
+        bytes
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn nonce(CspNonce(nonce): CspNonce) -> String {
+        nonce.as_str().to_string()
+    }
+
+    fn request() -> Request<Body> {
+        Request::builder().uri("/").body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn policy_contains_nonce() {
+        let app = Router::new()
+            .route("/", get(nonce))
+            .layer(CspLayer::default());
+
+        let res = app.oneshot(request()).await.unwrap();
+
+        let policy = res.headers()["Content-Security-Policy"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let nonce = crate::tests::body_text(res).await;
+        assert_eq!(32, nonce.len());
+        assert_eq!(
+            format!("script-src 'self' 'nonce-{nonce}'; style-src 'self' 'nonce-{nonce}'"),
+            policy
+        );
+    }
+
+    #[tokio::test]
+    async fn nonce_per_request() {
+        let app = Router::new()
+            .route("/", get(nonce))
+            .layer(CspLayer::new("script-src 'nonce-{nonce}'").unwrap());
+
+        let first = app.clone().oneshot(request()).await.unwrap();
+        let second = app.oneshot(request()).await.unwrap();
+
+        assert_ne!(
+            crate::tests::body_text(first).await,
+            crate::tests::body_text(second).await
+        );
+    }
+
+    #[tokio::test]
+    async fn handler_policy_is_kept() {
+        async fn handler() -> impl axum::response::IntoResponse {
+            (
+                [(header::CONTENT_SECURITY_POLICY, "default-src 'none'")],
+                "",
+            )
+        }
+        let app = Router::new()
+            .route("/", get(handler))
+            .layer(CspLayer::default());
+
+        let res = app.oneshot(request()).await.unwrap();
+
+        assert_eq!(
+            "default-src 'none'",
+            res.headers()["Content-Security-Policy"]
+        );
+    }
+
+    #[test]
+    fn invalid_policy() {
+        assert!(CspLayer::new("script-src 'nonce-{nonce}'\r\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn missing_layer() {
+        let app = Router::new().route("/", get(nonce));
+
+        let res = app.oneshot(request()).await.unwrap();
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+    }
+}
@@ -0,0 +1,143 @@
+//! Re-renders an invalid form with `422 Unprocessable Entity`.
+//!
+//! Turbo only renders the response to a form submission when it redirects or has a `4xx` or
+//! `5xx` status, so a form re-rendered with its errors and `200 OK` is silently dropped.
+//!
+//! ```
+//! use hotwire_turbo_axum::form::UnprocessableForm;
+//! use hotwire_turbo_axum::request::TurboRequest;
+//!
+//! async fn create(turbo: TurboRequest) -> UnprocessableForm {
+//!     // ... validation failed
+//!     let form = r#"<form id="message_form"><p class="error">Can't be blank</p></form>"#;
+//!     UnprocessableForm::new(&turbo, "message_form", form)
+//!         .page(|form| format!("<html><body><h1>New message</h1>{form}</body></html>"))
+//! }
+//! ```
+//!
+//! Requests accepting streams get a [`TurboStream`] replacing the form, other ones the page, or
+//! the form alone when there is no page.
+use crate::request::TurboRequest;
+use crate::TurboStream;
+use axum::body::Body;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use hotwire_turbo::stream;
+use std::fmt;
+
+type Page = Box<dyn FnOnce(String) -> Response + Send>;
+
+/// An invalid form, answered with `422 Unprocessable Entity`.
+#[must_use]
+pub struct UnprocessableForm {
+    accepts_turbo_stream: bool,
+    id: String,
+    form: String,
+    page: Option<Page>,
+}
+
+impl UnprocessableForm {
+    /// The form with its errors, `id` being the id of the form element it replaces.
+    pub fn new(request: &TurboRequest, id: &str, form: impl Into<String>) -> Self {
+        UnprocessableForm {
+            accepts_turbo_stream: request.accepts_turbo_stream(),
+            id: id.to_string(),
+            form: form.into(),
+            page: None,
+        }
+    }
+
+    /// Renders the full page around the form, for requests not accepting streams.
+    pub fn page<F, T>(mut self, render: F) -> Self
+    where
+        F: FnOnce(String) -> T + Send + 'static,
+        T: Into<Body>,
+    {
+        self.page = Some(Box::new(move |form| Html(render(form)).into_response()));
+        self
+    }
+}
+
+impl fmt::Debug for UnprocessableForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnprocessableForm")
+            .field("accepts_turbo_stream", &self.accepts_turbo_stream)
+            .field("id", &self.id)
+            .field("form", &self.form)
+            .field("page", &self.page.is_some())
+            .finish()
+    }
+}
+
+impl IntoResponse for UnprocessableForm {
+    fn into_response(self) -> Response {
+        let response = if self.accepts_turbo_stream {
+            TurboStream(stream::replace(&self.id, self.form)).into_response()
+        } else {
+            match self.page {
+                Some(render) => render(self.form),
+                None => Html(self.form).into_response(),
+            }
+        };
+        let mut response = (StatusCode::UNPROCESSABLE_ENTITY, response).into_response();
+        response
+            .headers_mut()
+            .append(header::VARY, HeaderValue::from_static("Accept"));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, Method};
+
+    const FORM: &str = r#"<form id="message_form">Can't be blank</form>"#;
+
+    fn turbo(accept: &str) -> TurboRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, accept.parse().unwrap());
+        TurboRequest::from_headers(Method::POST, &headers)
+    }
+
+    #[tokio::test]
+    async fn replaces_form_for_turbo() {
+        let res = UnprocessableForm::new(
+            &turbo("text/vnd.turbo-stream.html, text/html"),
+            "message_form",
+            FORM,
+        )
+        .page(|form| format!("<main>{form}</main>"))
+        .into_response();
+
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, res.status());
+        assert_eq!("text/vnd.turbo-stream.html", res.headers()["Content-Type"]);
+        assert_eq!("Accept", res.headers()["Vary"]);
+        assert_eq!(
+            stream::replace("message_form", FORM),
+            crate::tests::body_text(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn renders_page() {
+        let res = UnprocessableForm::new(&turbo("text/html"), "message_form", FORM)
+            .page(|form| format!("<main>{form}</main>"))
+            .into_response();
+
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, res.status());
+        assert_eq!("text/html; charset=utf-8", res.headers()["Content-Type"]);
+        assert_eq!(
+            format!("<main>{FORM}</main>"),
+            crate::tests::body_text(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn renders_form_without_page() {
+        let res = UnprocessableForm::new(&turbo("text/html"), "message_form", FORM).into_response();
+
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, res.status());
+        assert_eq!(FORM, crate::tests::body_text(res).await);
+    }
+}
@@ -0,0 +1,105 @@
+//! Responds to [htmx](https://htmx.org) clients with the same stream actions sent to Turbo ones.
+//!
+//! ```
+//! use axum::{routing::post, Router};
+//! use hotwire_turbo::stream;
+//! use hotwire_turbo_axum::htmx::Htmx;
+//!
+//! async fn create() -> Htmx<String> {
+//!     Htmx(stream::append("messages", "<p>Hi</p>"))
+//! }
+//!
+//! let app: Router = Router::new().route("/messages", post(create));
+//! ```
+use axum::http::{header, HeaderName, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use hotwire_turbo::htmx;
+
+/// Renders the stream actions as htmx out of band swaps and `HX-*` headers.
+///
+/// Payloads that can't be rendered for htmx respond with `500 Internal Server Error`.
+#[derive(Clone, Copy, Debug)]
+#[must_use]
+pub struct Htmx<T>(pub T);
+
+impl<T> IntoResponse for Htmx<T>
+where
+    T: AsRef<str>,
+{
+    fn into_response(self) -> Response {
+        let rendered = match htmx::render(self.0.as_ref()) {
+            Ok(rendered) => rendered,
+            Err(error) => {
+                return (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response()
+            }
+        };
+        let mut response = (
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/html; charset=utf-8"),
+            )],
+            rendered.body,
+        )
+            .into_response();
+        for (name, value) in rendered.headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|error| error.to_string())
+                .and_then(|name| {
+                    let value = HeaderValue::try_from(value).map_err(|error| error.to_string())?;
+                    Ok((name, value))
+                });
+            match header {
+                Ok((name, value)) => {
+                    response.headers_mut().insert(name, value);
+                }
+                Err(error) => return (StatusCode::INTERNAL_SERVER_ERROR, error).into_response(),
+            }
+        }
+        response
+    }
+}
+
+impl<T> From<T> for Htmx<T> {
+    fn from(inner: T) -> Self {
+        Self(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use hotwire_turbo::{power, stream};
+    use tower::ServiceExt;
+
+    async fn respond(payload: String) -> Response {
+        Router::new()
+            .route("/", get(move || async move { Htmx(payload) }))
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn swaps_and_headers() {
+        let res = respond(stream::remove("flash") + &power::push_state("/inbox", "", "")).await;
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!("text/html; charset=utf-8", res.headers()["Content-Type"]);
+        assert_eq!("/inbox", res.headers()["HX-Push-Url"]);
+        assert_eq!(
+            r##"<div hx-swap-oob="delete:#flash"></div>"##,
+            crate::tests::body_text(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_action() {
+        let res = respond(power::set_focus("#input")).await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+    }
+}
@@ -1,7 +1,23 @@
+#[cfg(feature = "assets")]
+pub mod assets;
+pub mod command;
+pub mod csp;
+pub mod form;
+pub mod htmx;
+#[cfg(feature = "mercure")]
+pub mod mercure;
+pub mod negotiate;
+pub mod path_configuration;
+pub mod redirect;
+pub mod request;
+
 use axum::body::Body;
-use axum::http::{header, HeaderValue};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 
+/// The media type of stream responses.
+pub(crate) const TURBO_STREAM: &str = "text/vnd.turbo-stream.html";
+
 /// A Hotwire TurboStream response.
 ///
 /// Will automatically get `Content-Type: text/vnd.turbo-stream.html`.
@@ -15,10 +31,7 @@ where
 {
     fn into_response(self) -> Response {
         (
-            [(
-                header::CONTENT_TYPE,
-                HeaderValue::from_static("text/vnd.turbo-stream.html"),
-            )],
+            [(header::CONTENT_TYPE, HeaderValue::from_static(TURBO_STREAM))],
             self.0.into(),
         )
             .into_response()
@@ -31,8 +44,33 @@ impl<T> From<T> for TurboStream<T> {
     }
 }
 
+/// Whether `If-None-Match` lists `etag`, for answering `304 Not Modified`.
+///
+/// The comparison is weak, as `If-None-Match` requires: `W/"a"` matches `"a"`.
+pub(crate) fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    fn opaque(tag: &str) -> &str {
+        let tag = tag.trim();
+        tag.strip_prefix("W/").unwrap_or(tag)
+    }
+    let etag = opaque(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// A redirect to `location`, or `500 Internal Server Error` when it isn't a valid header value.
+pub(crate) fn redirect(status: StatusCode, location: &str) -> Response {
+    match HeaderValue::try_from(location) {
+        Ok(location) => (status, [(header::LOCATION, location)]).into_response(),
+        Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use axum::{body::Body, http::Request, routing::get, Router};
     use http_body_util::BodyExt;
@@ -72,7 +110,20 @@ mod tests {
         assert_eq!(body, "Test");
     }
 
-    async fn body_text<B>(body: B) -> String
+    #[test]
+    fn weak_etags_match() {
+        let headers = |value: &'static str| {
+            HeaderMap::from_iter([(header::IF_NONE_MATCH, HeaderValue::from_static(value))])
+        };
+        assert!(etag_matches(&headers(r#""a""#), r#""a""#));
+        assert!(etag_matches(&headers(r#""b", W/"a""#), r#""a""#));
+        assert!(etag_matches(&headers(r#""a""#), r#"W/"a""#));
+        assert!(etag_matches(&headers("*"), r#""a""#));
+        assert!(!etag_matches(&headers(r#"W/"b""#), r#""a""#));
+        assert!(!etag_matches(&HeaderMap::new(), r#""a""#));
+    }
+
+    pub(crate) async fn body_text<B>(body: B) -> String
     where
         B: axum::body::HttpBody,
         B::Error: std::fmt::Debug,
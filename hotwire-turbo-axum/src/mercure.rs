@@ -0,0 +1,403 @@
+//! An embedded [Mercure](https://mercure.rocks) hub, delivering published updates to subscribers
+//! as server-sent events.
+//!
+//! ```
+//! use axum::Router;
+//! use hotwire_turbo::mercure::Update;
+//! use hotwire_turbo::stream;
+//! use hotwire_turbo_axum::mercure::Hub;
+//!
+//! let hub = Hub::new("!ChangeThisMercureHubJWTSecretKey!");
+//! let app: Router = Router::new().merge(hotwire_turbo_axum::mercure::router(hub.clone()));
+//!
+//! // Publishes in process, without going through HTTP.
+//! hub.publish(Update::new("/books/1", stream::remove("book_1")));
+//! ```
+//!
+//! Pages subscribe with `<turbo-stream-source src="/.well-known/mercure?topic=/books/1">`.
+//! Updates are only kept in memory and delivered to connected subscribers, so reconnecting
+//! subscribers don't receive missed updates.
+use axum::extract::RawQuery;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures_util::stream;
+use hotwire_turbo::mercure::{self, Claims, Update};
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Updates kept for subscribers that fall behind, which then skip what they missed.
+const CAPACITY: usize = 1024;
+
+struct Inner {
+    secret: Vec<u8>,
+    sender: broadcast::Sender<Arc<Update>>,
+    next_id: AtomicU64,
+}
+
+/// The hub state, shared by its routes and in-process publishers.
+#[derive(Clone)]
+pub struct Hub {
+    inner: Arc<Inner>,
+}
+
+impl Hub {
+    /// A hub verifying publisher and subscriber tokens signed with `secret`.
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Hub {
+            inner: Arc::new(Inner {
+                secret: secret.into(),
+                sender,
+                next_id: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Delivers the update to the current subscribers and returns its id, generated unless set.
+    pub fn publish(&self, update: Update) -> String {
+        let update = match update.id() {
+            Some(_) => update,
+            None => {
+                let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+                update.with_id(&format!("urn:hotwire-turbo:{id}"))
+            }
+        };
+        let id = update.id().unwrap_or_default().to_string();
+        // Sending only fails without subscribers.
+        let _ = self.inner.sender.send(Arc::new(update));
+        id
+    }
+
+    fn claims(&self, headers: &HeaderMap) -> Result<Option<Claims>, mercure::Error> {
+        token(headers)
+            .map(|token| Claims::verify(&self.inner.secret, token))
+            .transpose()
+    }
+
+    fn subscribe(&self, query: Option<String>, headers: &HeaderMap) -> Response {
+        let topics: Vec<String> = mercure::form_pairs(query.as_deref().unwrap_or_default())
+            .into_iter()
+            .filter(|(name, _)| name == "topic")
+            .map(|(_, topic)| topic)
+            .collect();
+        if topics.is_empty() {
+            return (StatusCode::BAD_REQUEST, "missing `topic`").into_response();
+        }
+        let claims = match self.claims(headers) {
+            Ok(claims) => claims,
+            Err(error) => return (StatusCode::UNAUTHORIZED, error.to_string()).into_response(),
+        };
+        let receiver = self.inner.sender.subscribe();
+        let events = stream::unfold(receiver, move |mut receiver| {
+            let topics = topics.clone();
+            let claims = claims.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(update) if deliverable(&update, &topics, claims.as_ref()) => {
+                            return Some((Ok::<_, Infallible>(event(&update)), receiver))
+                        }
+                        Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        });
+        Sse::new(events)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    }
+
+    fn receive(&self, headers: &HeaderMap, body: &str) -> Response {
+        let claims = match self.claims(headers) {
+            Ok(Some(claims)) => claims,
+            Ok(None) => return (StatusCode::UNAUTHORIZED, "missing token").into_response(),
+            Err(error) => return (StatusCode::UNAUTHORIZED, error.to_string()).into_response(),
+        };
+        let update = match Update::from_form(body) {
+            Ok(update) => update,
+            Err(error) => return (StatusCode::BAD_REQUEST, error.to_string()).into_response(),
+        };
+        if let Some(topic) = update
+            .topics()
+            .iter()
+            .find(|topic| !claims.can_publish(topic))
+        {
+            let message = format!("not allowed to publish to `{topic}`");
+            return (StatusCode::FORBIDDEN, message).into_response();
+        }
+        self.publish(update).into_response()
+    }
+}
+
+impl fmt::Debug for Hub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hub")
+            .field("subscribers", &self.inner.sender.receiver_count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A router serving the hub at [`mercure::PATH`]: `GET` subscribes, `POST` publishes.
+pub fn router<S>(hub: Hub) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let publisher = hub.clone();
+    Router::new().route(
+        mercure::PATH,
+        get(
+            move |RawQuery(query): RawQuery, headers: HeaderMap| async move {
+                hub.subscribe(query, &headers)
+            },
+        )
+        .post(move |headers: HeaderMap, body: String| async move {
+            publisher.receive(&headers, &body)
+        }),
+    )
+}
+
+/// The bearer token, or the one in the [`mercure::COOKIE`] cookie.
+fn token(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer.or_else(|| {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == mercure::COOKIE)
+            .map(|(_, token)| token)
+    })
+}
+
+fn deliverable(update: &Update, topics: &[String], claims: Option<&Claims>) -> bool {
+    let subscribed = update.topics().iter().any(|topic| {
+        topics
+            .iter()
+            .any(|selector| mercure::matches(selector, topic))
+    });
+    let authorized = !update.is_private()
+        || claims.is_some_and(|claims| {
+            update
+                .topics()
+                .iter()
+                .any(|topic| claims.can_subscribe(topic))
+        });
+    subscribed && authorized
+}
+
+/// The server-sent event for the update. Line breaks in the data are normalized to `\n`, which
+/// splits it across `data` lines. An `id` or `type` with a line break or NUL can only come from
+/// an in-process update, and is left out rather than ending the field early.
+fn event(update: &Update) -> Event {
+    let data = update.data().replace("\r\n", "\n").replace('\r', "\n");
+    let mut event = Event::default().data(data);
+    let valid = |value: &&str| !value.contains(['\r', '\n', '\0']);
+    if let Some(id) = update.id().filter(valid) {
+        event = event.id(id);
+    }
+    if let Some(event_type) = update.event_type().filter(valid) {
+        event = event.event(event_type);
+    }
+    if let Some(retry) = update.retry() {
+        event = event.retry(Duration::from_millis(retry));
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use hotwire_turbo::mercure::PublishRequestBuilder;
+    use hotwire_turbo::stream as turbo_stream;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "secret";
+
+    fn subscription(query: &str, token: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder().uri(format!("{}?{query}", mercure::PATH));
+        if let Some(token) = token {
+            request = request.header(header::COOKIE, format!("{}={token}", mercure::COOKIE));
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    fn publication(builder: &PublishRequestBuilder, update: &Update) -> Request<Body> {
+        let request = builder.build(update);
+        Request::builder()
+            .method("POST")
+            .uri(request.url)
+            .header(header::AUTHORIZATION, request.authorization)
+            .header(header::CONTENT_TYPE, mercure::CONTENT_TYPE)
+            .body(Body::from(request.body))
+            .unwrap()
+    }
+
+    async fn next_event(res: Response) -> String {
+        let frame = res.into_body().frame().await.unwrap().unwrap();
+        String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn publish_and_subscribe() {
+        let app: Router = router(Hub::new(SECRET));
+        let res = app
+            .clone()
+            .oneshot(subscription("topic=%2Fbooks%2F1", None))
+            .await
+            .unwrap();
+        assert_eq!("text/event-stream", res.headers()["Content-Type"]);
+
+        let publisher = PublishRequestBuilder::new(mercure::PATH, SECRET.as_bytes());
+        let published = app
+            .clone()
+            .oneshot(publication(
+                &publisher,
+                &Update::new("/books/2", turbo_stream::remove("book_2")),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, published.status());
+        let published = app
+            .oneshot(publication(
+                &publisher,
+                &Update::new("/books/1", turbo_stream::remove("book_1")),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            "urn:hotwire-turbo:2",
+            crate::tests::body_text(published).await
+        );
+
+        assert_eq!(
+            format!(
+                "data: {}\nid: urn:hotwire-turbo:2\n\n",
+                turbo_stream::remove("book_1")
+            ),
+            next_event(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn private_updates() {
+        let hub = Hub::new(SECRET);
+        let app: Router = router(hub.clone());
+        let token = Claims::new().subscribe("/books/1").sign(SECRET.as_bytes());
+        let anonymous = app
+            .clone()
+            .oneshot(subscription("topic=*", None))
+            .await
+            .unwrap();
+        let authorized = app
+            .oneshot(subscription("topic=*", Some(&token)))
+            .await
+            .unwrap();
+
+        hub.publish(Update::new("/books/1", "secret").private());
+        hub.publish(Update::new("/books/1", "public"));
+
+        assert!(next_event(authorized).await.starts_with("data: secret\n"));
+        assert!(next_event(anonymous).await.starts_with("data: public\n"));
+    }
+
+    #[tokio::test]
+    async fn unauthorized_publishers() {
+        let app: Router = router(Hub::new(SECRET));
+        let update = Update::new("/books/1", "data");
+
+        let res = app
+            .clone()
+            .oneshot(publication(
+                &PublishRequestBuilder::new(mercure::PATH, b"other"),
+                &update,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::UNAUTHORIZED, res.status());
+
+        let token = Claims::new().publish("/books/2").sign(SECRET.as_bytes());
+        let res = app
+            .oneshot(publication(
+                &PublishRequestBuilder::with_token(mercure::PATH, &token),
+                &update,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::FORBIDDEN, res.status());
+    }
+
+    #[tokio::test]
+    async fn line_breaks_in_data() {
+        let hub = Hub::new(SECRET);
+        let app: Router = router(hub.clone());
+        let res = app.oneshot(subscription("topic=*", None)).await.unwrap();
+
+        hub.publish(
+            Update::new("/books/1", "<p>\r\nDune\r</p>")
+                .with_id("urn:1\r\nevent: forged")
+                .with_event_type("book\n"),
+        );
+
+        assert_eq!(
+            "data: <p>\ndata: Dune\ndata: </p>\n\n",
+            next_event(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn nul_in_id() {
+        let hub = Hub::new(SECRET);
+        let app: Router = router(hub.clone());
+        let res = app.oneshot(subscription("topic=*", None)).await.unwrap();
+
+        hub.publish(
+            Update::new("/books/1", "x")
+                .with_id("urn:\0")
+                .with_event_type("book"),
+        );
+
+        assert_eq!("data: x\nevent: book\n\n", next_event(res).await);
+    }
+
+    #[tokio::test]
+    async fn line_breaks_in_id_and_type() {
+        let app: Router = router(Hub::new(SECRET));
+        let builder = PublishRequestBuilder::new(mercure::PATH, SECRET.as_bytes());
+
+        for update in [
+            Update::new("/books/1", "x").with_id("urn:1\nevent: forged"),
+            Update::new("/books/1", "x").with_event_type("book\r"),
+            Update::new("/books/1", "x").with_id("\0"),
+        ] {
+            let res = app
+                .clone()
+                .oneshot(publication(&builder, &update))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::BAD_REQUEST, res.status());
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_topic() {
+        let app: Router = router(Hub::new(SECRET));
+        let res = app.oneshot(subscription("", None)).await.unwrap();
+        assert_eq!(StatusCode::BAD_REQUEST, res.status());
+    }
+}
@@ -0,0 +1,197 @@
+//! Responds with a stream, a full page or a redirect, depending on what the client accepts.
+//!
+//! ```
+//! use axum::response::IntoResponse;
+//! use hotwire_turbo::stream;
+//! use hotwire_turbo_axum::negotiate::Negotiate;
+//! use hotwire_turbo_axum::request::TurboRequest;
+//!
+//! async fn create(turbo: TurboRequest) -> impl IntoResponse {
+//!     // ... save the message
+//!     Negotiate::new(&turbo)
+//!         .turbo_stream(|| stream::append("messages", "<p>Hi</p>"))
+//!         .html(|| "<html><body><p>Hi</p></body></html>")
+//!         .redirect(|| "/messages".to_string())
+//! }
+//! ```
+//!
+//! Only the chosen closure is called:
+//!
+//! 1. the stream, when `Accept` lists `text/vnd.turbo-stream.html`,
+//! 2. the redirect, as `303 See Other`, for other requests than `GET` and `HEAD`, e.g. form
+//!    submissions without JavaScript,
+//! 3. the page otherwise, or when there is no redirect.
+//!
+//! `406 Not Acceptable` answers requests none of the given formats suit. Every response gets
+//! `Vary: Accept`, so caches don't serve a stream to a full page request.
+use crate::request::TurboRequest;
+use crate::{redirect, TurboStream};
+use axum::body::Body;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use std::fmt;
+
+type Render = Box<dyn FnOnce() -> Response + Send>;
+
+/// One closure per response format, the one matching the request being called.
+#[must_use]
+pub struct Negotiate {
+    accepts_turbo_stream: bool,
+    safe: bool,
+    turbo_stream: Option<Render>,
+    html: Option<Render>,
+    redirect: Option<Box<dyn FnOnce() -> String + Send>>,
+}
+
+impl Negotiate {
+    pub fn new(request: &TurboRequest) -> Self {
+        Negotiate {
+            accepts_turbo_stream: request.accepts_turbo_stream(),
+            safe: matches!(*request.method(), Method::GET | Method::HEAD),
+            turbo_stream: None,
+            html: None,
+            redirect: None,
+        }
+    }
+
+    /// Renders the stream actions for Turbo clients, as a [`TurboStream`].
+    pub fn turbo_stream<F, T>(mut self, render: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Into<Body>,
+    {
+        self.turbo_stream = Some(Box::new(move || TurboStream(render()).into_response()));
+        self
+    }
+
+    /// Renders the full page for other clients.
+    pub fn html<F, T>(mut self, render: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Into<Body>,
+    {
+        self.html = Some(Box::new(move || Html(render()).into_response()));
+        self
+    }
+
+    /// Returns the location to redirect form submissions without JavaScript to.
+    pub fn redirect<F>(mut self, location: F) -> Self
+    where
+        F: FnOnce() -> String + Send + 'static,
+    {
+        self.redirect = Some(Box::new(location));
+        self
+    }
+}
+
+impl fmt::Debug for Negotiate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Negotiate")
+            .field("accepts_turbo_stream", &self.accepts_turbo_stream)
+            .field("safe", &self.safe)
+            .field("turbo_stream", &self.turbo_stream.is_some())
+            .field("html", &self.html.is_some())
+            .field("redirect", &self.redirect.is_some())
+            .finish()
+    }
+}
+
+impl IntoResponse for Negotiate {
+    fn into_response(self) -> Response {
+        let mut response = match self {
+            Negotiate {
+                accepts_turbo_stream: true,
+                turbo_stream: Some(render),
+                ..
+            } => render(),
+            Negotiate {
+                safe: false,
+                redirect: Some(location),
+                ..
+            } => redirect(StatusCode::SEE_OTHER, &location()),
+            Negotiate {
+                html: Some(render), ..
+            } => render(),
+            Negotiate {
+                redirect: Some(location),
+                ..
+            } => redirect(StatusCode::SEE_OTHER, &location()),
+            _ => StatusCode::NOT_ACCEPTABLE.into_response(),
+        };
+        response
+            .headers_mut()
+            .append(header::VARY, HeaderValue::from_static("Accept"));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use axum::routing::post;
+    use axum::Router;
+    use hotwire_turbo::stream;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let handler = |turbo: TurboRequest| async move {
+            Negotiate::new(&turbo)
+                .turbo_stream(|| stream::remove("flash"))
+                .html(|| "<p>Page</p>")
+                .redirect(|| "/messages".to_string())
+        };
+        Router::new().route("/", post(handler).get(handler))
+    }
+
+    async fn send(method: &str, accept: &str) -> Response {
+        app()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri("/")
+                    .header(header::ACCEPT, accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn turbo_stream() {
+        let res = send("POST", "text/vnd.turbo-stream.html, text/html").await;
+
+        assert_eq!("text/vnd.turbo-stream.html", res.headers()["Content-Type"]);
+        assert_eq!("Accept", res.headers()["Vary"]);
+        assert_eq!(stream::remove("flash"), crate::tests::body_text(res).await);
+    }
+
+    #[tokio::test]
+    async fn html() {
+        let res = send("GET", "text/html, */*").await;
+
+        assert_eq!("text/html; charset=utf-8", res.headers()["Content-Type"]);
+        assert_eq!("Accept", res.headers()["Vary"]);
+        assert_eq!("<p>Page</p>", crate::tests::body_text(res).await);
+    }
+
+    #[tokio::test]
+    async fn redirect() {
+        let res = send("POST", "text/html").await;
+
+        assert_eq!(StatusCode::SEE_OTHER, res.status());
+        assert_eq!("/messages", res.headers()["Location"]);
+        assert_eq!("Accept", res.headers()["Vary"]);
+    }
+
+    #[tokio::test]
+    async fn not_acceptable() {
+        let turbo = TurboRequest::from_headers(Method::GET, &Default::default());
+        let res = Negotiate::new(&turbo)
+            .turbo_stream(|| stream::remove("flash"))
+            .into_response();
+
+        assert_eq!(StatusCode::NOT_ACCEPTABLE, res.status());
+    }
+}
@@ -0,0 +1,158 @@
+//! Serves a Hotwire Native [`PathConfiguration`] as JSON.
+//!
+//! ```
+//! use axum::Router;
+//! use hotwire_turbo::path_configuration::{Context, PathConfiguration, Rule};
+//!
+//! let configuration = PathConfiguration::new()
+//!     .rule(Rule::new(&["/new$"]).context(Context::Modal));
+//!
+//! let app: Router = Router::new().merge(hotwire_turbo_axum::path_configuration::router(
+//!     "/configurations/ios_v1.json",
+//!     &configuration,
+//! )?);
+//! # Ok::<(), hotwire_turbo::path_configuration::Error>(())
+//! ```
+//!
+//! Both routers validate the configuration up front, so an invalid one fails at startup rather
+//! than in the apps.
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use hotwire_turbo::path_configuration::{Error, PathConfiguration};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+const CACHE_CONTROL: &str = "public, max-age=3600";
+
+/// A router serving the configuration at `path`, cached for an hour and revalidated by `ETag`.
+pub fn router<S>(path: &str, configuration: &PathConfiguration) -> Result<Router<S>, Error>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    serve_at(path, configuration, CACHE_CONTROL)
+}
+
+/// A router serving the configuration at `path` with `Cache-Control: no-store`, for tests and
+/// development, where apps should see every change on their next launch.
+pub fn uncached_router<S>(path: &str, configuration: &PathConfiguration) -> Result<Router<S>, Error>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    serve_at(path, configuration, "no-store")
+}
+
+fn serve_at<S>(
+    path: &str,
+    configuration: &PathConfiguration,
+    cache_control: &'static str,
+) -> Result<Router<S>, Error>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let json = configuration.to_json()?;
+    let etag = etag(&json);
+    let json: Arc<str> = json.into();
+    Ok(Router::new().route(
+        path,
+        get(move |headers: HeaderMap| async move { serve(&json, &etag, cache_control, &headers) }),
+    ))
+}
+
+/// The first 64 bits of the SHA-256 of the JSON, stable across builds and Rust versions.
+fn etag(json: &str) -> String {
+    let digest: String = Sha256::digest(json)[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!(r#""{digest}""#)
+}
+
+fn serve(json: &str, etag: &str, cache_control: &'static str, headers: &HeaderMap) -> Response {
+    let cache_headers = [
+        (
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        ),
+        (header::ETAG, HeaderValue::from_str(etag).expect("hex hash")),
+    ];
+    if crate::etag_matches(headers, etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+    (
+        cache_headers,
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )],
+        json.to_string(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use hotwire_turbo::path_configuration::{Context, Rule};
+    use tower::ServiceExt;
+
+    const PATH: &str = "/configurations/android_v1.json";
+
+    fn configuration() -> PathConfiguration {
+        PathConfiguration::new().rule(Rule::new(&["/new$"]).context(Context::Modal))
+    }
+
+    fn get(if_none_match: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder().uri(PATH);
+        if let Some(etag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_json() {
+        let app: Router = router(PATH, &configuration()).unwrap();
+        let res = app.oneshot(get(None)).await.unwrap();
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!("application/json", res.headers()["Content-Type"]);
+        assert_eq!(CACHE_CONTROL, res.headers()["Cache-Control"]);
+        assert_eq!(
+            configuration().to_json().unwrap(),
+            crate::tests::body_text(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn not_modified() {
+        let app: Router = router(PATH, &configuration()).unwrap();
+        let res = app.clone().oneshot(get(None)).await.unwrap();
+        let etag = res.headers()["ETag"].to_str().unwrap().to_string();
+
+        let res = app.oneshot(get(Some(&etag))).await.unwrap();
+        assert_eq!(StatusCode::NOT_MODIFIED, res.status());
+    }
+
+    #[test]
+    fn stable_etag() {
+        // `printf '{}' | sha256sum`
+        assert_eq!(r#""44136fa355b3678a""#, etag("{}"));
+    }
+
+    #[tokio::test]
+    async fn uncached() {
+        let app: Router = uncached_router(PATH, &configuration()).unwrap();
+        let res = app.oneshot(get(None)).await.unwrap();
+
+        assert_eq!("no-store", res.headers()["Cache-Control"]);
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        let configuration = PathConfiguration::new().rule(Rule::new(&[]));
+        assert!(router::<()>(PATH, &configuration).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Redirects the way Turbo expects after form submissions.
+//!
+//! ```
+//! use hotwire_turbo_axum::redirect::TurboRedirect;
+//! use hotwire_turbo_axum::request::TurboRequest;
+//!
+//! async fn update(turbo: TurboRequest) -> TurboRedirect {
+//!     // ... save the message
+//!     TurboRedirect::to(&turbo, "/messages/1")
+//! }
+//! ```
+//!
+//! Turbo Drive only follows `303 See Other` after a non-`GET` form submission, so that is what
+//! those get. Requests made from a Turbo Frame or accepting streams instead get a Turbo Power
+//! `redirect_to` action, which visits the location with the whole page rather than the frame.
+//! Every response gets `Vary: Accept, Turbo-Frame`, as both headers choose between them.
+use crate::request::TurboRequest;
+use crate::{redirect, TurboStream};
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use hotwire_turbo::drive::Action;
+use hotwire_turbo::power;
+
+/// Redirects to a location with the response the request's Turbo context calls for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct TurboRedirect {
+    location: String,
+    stream: bool,
+    safe: bool,
+    action: Action,
+}
+
+impl TurboRedirect {
+    pub fn to(request: &TurboRequest, location: &str) -> Self {
+        TurboRedirect {
+            location: location.to_string(),
+            stream: request.frame().is_some() || request.accepts_turbo_stream(),
+            safe: matches!(*request.method(), Method::GET | Method::HEAD),
+            action: Action::Advance,
+        }
+    }
+
+    /// The Turbo visit action of the `redirect_to` stream, `advance` by default.
+    pub fn action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+}
+
+impl IntoResponse for TurboRedirect {
+    fn into_response(self) -> Response {
+        let mut response = if self.stream {
+            TurboStream(power::redirect_to(
+                &self.location,
+                self.action.as_str(),
+                None,
+            ))
+            .into_response()
+        } else if self.safe {
+            redirect(StatusCode::FOUND, &self.location)
+        } else {
+            redirect(StatusCode::SEE_OTHER, &self.location)
+        };
+        response.headers_mut().append(
+            header::VARY,
+            HeaderValue::from_static("Accept, Turbo-Frame"),
+        );
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderMap;
+
+    fn redirect_to(method: Method, headers: &[(header::HeaderName, &str)]) -> Response {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(name.clone(), value.parse().unwrap());
+        }
+        TurboRedirect::to(&TurboRequest::from_headers(method, &map), "/messages").into_response()
+    }
+
+    #[test]
+    fn see_other_after_form_submission() {
+        let res = redirect_to(Method::PATCH, &[(header::ACCEPT, "text/html")]);
+        assert_eq!(StatusCode::SEE_OTHER, res.status());
+        assert_eq!("/messages", res.headers()["Location"]);
+        assert_eq!("Accept, Turbo-Frame", res.headers()["Vary"]);
+    }
+
+    #[test]
+    fn found_after_get() {
+        let res = redirect_to(Method::GET, &[]);
+        assert_eq!(StatusCode::FOUND, res.status());
+        assert_eq!("Accept, Turbo-Frame", res.headers()["Vary"]);
+    }
+
+    #[tokio::test]
+    async fn stream_in_frame() {
+        let frame = header::HeaderName::from_static("turbo-frame");
+        let res = redirect_to(Method::POST, &[(frame, "modal")]);
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!("text/vnd.turbo-stream.html", res.headers()["Content-Type"]);
+        assert_eq!("Accept, Turbo-Frame", res.headers()["Vary"]);
+        assert_eq!(
+            power::redirect_to("/messages", "advance", None),
+            crate::tests::body_text(res).await
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_when_accepted() {
+        let turbo = TurboRequest::from_headers(Method::POST, &{
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::ACCEPT,
+                "text/vnd.turbo-stream.html".parse().unwrap(),
+            );
+            headers
+        });
+        let res = TurboRedirect::to(&turbo, "/messages")
+            .action(Action::Replace)
+            .into_response();
+        assert_eq!(
+            power::redirect_to("/messages", "replace", None),
+            crate::tests::body_text(res).await
+        );
+    }
+}
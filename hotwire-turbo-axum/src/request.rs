@@ -0,0 +1,195 @@
+//! Reports what a request tells about the Turbo client that sent it.
+//!
+//! ```
+//! use hotwire_turbo::stream;
+//! use hotwire_turbo_axum::request::TurboRequest;
+//! use hotwire_turbo_axum::TurboStream;
+//! use axum::response::{IntoResponse, Response};
+//!
+//! async fn dismiss(turbo: TurboRequest) -> Response {
+//!     if turbo.accepts_turbo_stream() {
+//!         TurboStream(stream::remove("flash")).into_response()
+//!     } else {
+//!         "Dismissed".into_response()
+//!     }
+//! }
+//! ```
+use crate::TURBO_STREAM;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, Method};
+use std::convert::Infallible;
+
+/// The header Turbo sets to the id of the frame making the request.
+pub const TURBO_FRAME: &str = "Turbo-Frame";
+
+/// The header Turbo sets to a unique id per request.
+pub const TURBO_REQUEST_ID: &str = "X-Turbo-Request-Id";
+
+/// The Turbo context of a request. Never rejects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurboRequest {
+    method: Method,
+    accepts_turbo_stream: bool,
+    frame: Option<String>,
+    request_id: Option<String>,
+    native: bool,
+}
+
+impl TurboRequest {
+    pub fn from_headers(method: Method, headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let native = headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|agent| {
+                agent.contains("Hotwire Native") || agent.contains("Turbo Native")
+            });
+        TurboRequest {
+            method,
+            accepts_turbo_stream: media_ranges(headers)
+                .iter()
+                .any(|(range, quality)| range == TURBO_STREAM && *quality > 0.0),
+            frame: header(TURBO_FRAME),
+            request_id: header(TURBO_REQUEST_ID),
+            native,
+        }
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Whether `Accept` lists `text/vnd.turbo-stream.html` with a non-zero quality. Wildcards
+    /// don't count, as every browser sends `*/*`.
+    pub fn accepts_turbo_stream(&self) -> bool {
+        self.accepts_turbo_stream
+    }
+
+    /// The id of the Turbo Frame making the request.
+    pub fn frame(&self) -> Option<&str> {
+        self.frame.as_deref()
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Whether the user agent is a Hotwire Native, or Turbo Native, app.
+    pub fn is_native(&self) -> bool {
+        self.native
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for TurboRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(parts.method.clone(), &parts.headers))
+    }
+}
+
+/// The lowercased media ranges of `Accept` with their quality.
+pub(crate) fn media_ranges(headers: &HeaderMap) -> Vec<(String, f32)> {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|range| {
+            let mut parameters = range.split(';');
+            let media_range = parameters.next()?.trim().to_ascii_lowercase();
+            if media_range.is_empty() {
+                return None;
+            }
+            let quality = parameters
+                .filter_map(|parameter| parameter.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .map_or(1.0, |(_, quality)| quality.trim().parse().unwrap_or(0.0));
+            Some((media_range, quality))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn turbo(headers: &[(&str, &str)]) -> TurboRequest {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(
+                header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        TurboRequest::from_headers(Method::POST, &map)
+    }
+
+    #[test]
+    fn accepts_turbo_stream() {
+        let accept = |value| turbo(&[("Accept", value)]).accepts_turbo_stream();
+        assert!(accept(
+            "text/vnd.turbo-stream.html, text/html, application/xhtml+xml"
+        ));
+        assert!(accept("text/html;q=0.9, TEXT/VND.TURBO-STREAM.HTML;q=0.5"));
+        assert!(!accept("text/vnd.turbo-stream.html;q=0, text/html"));
+        assert!(!accept("text/html, */*"));
+        assert!(!turbo(&[]).accepts_turbo_stream());
+    }
+
+    #[test]
+    fn frame_request_id_and_native() {
+        let request = turbo(&[
+            ("Turbo-Frame", "comments"),
+            ("X-Turbo-Request-Id", "1b2c"),
+            (
+                "User-Agent",
+                "Mozilla/5.0 (iPhone) Hotwire Native iOS; Turbo Native iOS",
+            ),
+        ]);
+        assert_eq!(Some("comments"), request.frame());
+        assert_eq!(Some("1b2c"), request.request_id());
+        assert!(request.is_native());
+        assert!(!turbo(&[("User-Agent", "Mozilla/5.0 (iPhone)")]).is_native());
+    }
+
+    #[tokio::test]
+    async fn extractor() {
+        let app =
+            Router::new().route(
+                "/",
+                post(|turbo: TurboRequest| async move {
+                    format!("{} {:?}", turbo.method(), turbo.frame())
+                }),
+            );
+        let res = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/")
+                    .header("Turbo-Frame", "modal")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(r#"POST Some("modal")"#, crate::tests::body_text(res).await);
+    }
+}
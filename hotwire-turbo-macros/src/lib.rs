@@ -0,0 +1,320 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr, Result, Type};
+
+/// Derives `hotwire_turbo::TurboAction` and `Display` for a custom Turbo Stream action.
+///
+/// The action name is given on the struct with `#[turbo(action = "...")]`. Fields become part of
+/// the tag through one of the following field attributes, all other fields are ignored:
+///
+/// * `#[turbo(attr)]` or `#[turbo(attr = "name")]` renders the field as an attribute
+/// * `#[turbo(target)]` renders the field as the `target` attribute
+/// * `#[turbo(targets)]` renders the field as the `targets` attribute
+/// * `#[turbo(template)]` renders the field as the template content
+///
+/// Values are rendered with their `Display` implementation and `Option` fields are left out
+/// when `None`.
+///
+/// `action`, `target` and `targets` can't be used as attribute names, and an attribute can only
+/// be rendered once:
+///
+/// ```compile_fail
+/// use hotwire_turbo::TurboAction;
+///
+/// #[derive(TurboAction)]
+/// #[turbo(action = "highlight")]
+/// struct Highlight {
+///     #[turbo(attr = "target")]
+///     selector: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use hotwire_turbo::TurboAction;
+///
+/// #[derive(TurboAction)]
+/// #[turbo(action = "highlight")]
+/// struct Highlight {
+///     #[turbo(attr)]
+///     action: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use hotwire_turbo::TurboAction;
+///
+/// #[derive(TurboAction)]
+/// #[turbo(action = "highlight")]
+/// struct Highlight {
+///     #[turbo(targets)]
+///     selector: String,
+///     #[turbo(attr = "targets")]
+///     fallback: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use hotwire_turbo::TurboAction;
+///
+/// #[derive(TurboAction)]
+/// #[turbo(action = "highlight")]
+/// struct Highlight {
+///     #[turbo(attr = "data-color")]
+///     color: String,
+///     #[turbo(attr = "data-color")]
+///     fallback_color: String,
+/// }
+/// ```
+#[proc_macro_derive(TurboAction, attributes(turbo))]
+pub fn derive_turbo_action(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum Role {
+    Attribute(String),
+    Template,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let action = action_name(&input)?;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "TurboAction can only be derived for structs",
+            ))
+        }
+    };
+    if let Fields::Unnamed(fields) = fields {
+        return Err(Error::new_spanned(
+            fields,
+            "TurboAction can't be derived for tuple structs",
+        ));
+    }
+
+    let mut names = Vec::new();
+    let mut attributes = Vec::new();
+    let mut template = None;
+    for field in fields {
+        let Some(role) = field_role(field)? else {
+            continue;
+        };
+        let ident = field.ident.as_ref().expect("named field");
+        let optional = is_option(&field.ty);
+        match role {
+            Role::Attribute(name) => {
+                if names.contains(&name) {
+                    return Err(Error::new_spanned(
+                        field,
+                        format!("duplicate attribute `{name}`"),
+                    ));
+                }
+                let value = quote::format_ident!("__{}", ident.unraw());
+                let render = if optional {
+                    quote! {
+                        let #value = self.#ident.as_ref().map(::std::string::ToString::to_string);
+                        if let ::std::option::Option::Some(value) = &#value {
+                            attributes.insert(#name, value.as_str());
+                        }
+                    }
+                } else {
+                    quote! {
+                        let #value = ::std::string::ToString::to_string(&self.#ident);
+                        attributes.insert(#name, #value.as_str());
+                    }
+                };
+                attributes.push(render);
+                names.push(name);
+            }
+            Role::Template => {
+                if template.is_some() {
+                    return Err(Error::new_spanned(
+                        field,
+                        "only one field can be marked #[turbo(template)]",
+                    ));
+                }
+                template = Some(if optional {
+                    quote! {
+                        self.#ident.as_ref().map(::std::string::ToString::to_string).unwrap_or_default()
+                    }
+                } else {
+                    quote! { ::std::string::ToString::to_string(&self.#ident) }
+                });
+            }
+        }
+    }
+    let template = template.unwrap_or_else(|| quote! { "" });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::hotwire_turbo::TurboAction for #ident #ty_generics #where_clause {
+            fn render(&self) -> ::std::string::String {
+                let mut attributes = ::hotwire_turbo::__private::BTreeMap::new();
+                attributes.insert("action", #action);
+                #(#attributes)*
+                ::hotwire_turbo::__private::turbo_stream_tag(&mut attributes, #template)
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&::hotwire_turbo::TurboAction::render(self))
+            }
+        }
+    })
+}
+
+fn action_name(input: &DeriveInput) -> Result<String> {
+    let mut action = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("turbo"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("action") {
+                action = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `action = \"...\"`"))
+            }
+        })?;
+    }
+    action.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "missing #[turbo(action = \"...\")] on the struct",
+        )
+    })
+}
+
+fn field_role(field: &syn::Field) -> Result<Option<Role>> {
+    let mut role = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("turbo"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if role.is_some() {
+                return Err(meta.error("a field can only have one #[turbo(...)] role"));
+            }
+            if meta.path.is_ident("attr") {
+                let name = if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<LitStr>()?.value()
+                } else {
+                    field
+                        .ident
+                        .as_ref()
+                        .expect("named field")
+                        .unraw()
+                        .to_string()
+                };
+                match name.as_str() {
+                    "action" => {
+                        return Err(meta.error(
+                            "`action` is reserved, set it with #[turbo(action = \"...\")] on the struct",
+                        ))
+                    }
+                    "target" | "targets" => {
+                        return Err(meta.error(format!(
+                            "`{name}` is reserved, use #[turbo({name})] instead"
+                        )))
+                    }
+                    _ => role = Some(Role::Attribute(name)),
+                }
+            } else if meta.path.is_ident("target") {
+                role = Some(Role::Attribute("target".to_string()));
+            } else if meta.path.is_ident("targets") {
+                role = Some(Role::Attribute("targets".to_string()));
+            } else if meta.path.is_ident("template") {
+                role = Some(Role::Template);
+            } else {
+                return Err(meta.error("expected `attr`, `target`, `targets` or `template`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(role)
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use syn::parse_quote;
+
+    fn error(input: DeriveInput) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reserved_attribute_names() {
+        assert_eq!(
+            "`action` is reserved, set it with #[turbo(action = \"...\")] on the struct",
+            error(parse_quote! {
+                #[turbo(action = "highlight")]
+                struct Highlight {
+                    #[turbo(attr)]
+                    action: String,
+                }
+            })
+        );
+        assert_eq!(
+            "`target` is reserved, use #[turbo(target)] instead",
+            error(parse_quote! {
+                #[turbo(action = "highlight")]
+                struct Highlight {
+                    #[turbo(attr = "target")]
+                    selector: String,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_attribute_names() {
+        assert_eq!(
+            "duplicate attribute `targets`",
+            error(parse_quote! {
+                #[turbo(action = "highlight")]
+                struct Highlight {
+                    #[turbo(targets)]
+                    selector: String,
+                    #[turbo(targets)]
+                    fallback: String,
+                }
+            })
+        );
+        assert_eq!(
+            "duplicate attribute `data-color`",
+            error(parse_quote! {
+                #[turbo(action = "highlight")]
+                struct Highlight {
+                    #[turbo(attr = "data-color")]
+                    color: String,
+                    #[turbo(attr = "data-color")]
+                    fallback_color: String,
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use hotwire_turbo::{stream, turbo_stream, TurboAction};
+use pretty_assertions::assert_eq;
+use std::fmt;
+
+enum Level {
+    Info,
+    Alert,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info => f.write_str("info"),
+            Level::Alert => f.write_str("alert"),
+        }
+    }
+}
+
+#[derive(TurboAction)]
+#[turbo(action = "toast")]
+struct Toast {
+    #[turbo(attr)]
+    level: Level,
+    #[turbo(template)]
+    body: String,
+}
+
+#[derive(TurboAction)]
+#[turbo(action = "highlight")]
+struct Highlight<'a> {
+    #[turbo(targets)]
+    selector: &'a str,
+    #[turbo(attr = "data-color")]
+    color: Option<&'a str>,
+    #[turbo(attr)]
+    r#type: &'a str,
+    #[allow(dead_code)]
+    ignored: u32,
+}
+
+#[derive(TurboAction)]
+#[turbo(action = "ping")]
+struct Ping;
+
+#[test]
+fn attribute_and_template() {
+    let expected = r#"<turbo-stream action="toast" level="alert"><template><p>Saved</p></template></turbo-stream>"#;
+    let toast = Toast {
+        level: Level::Alert,
+        body: "<p>Saved</p>".to_string(),
+    };
+    assert_eq!(expected, toast.render());
+}
+
+#[test]
+fn attributes_are_escaped_and_ordered() {
+    let expected = r##"<turbo-stream action="highlight" data-color="&quot;red&quot;" targets="#message" type="flash"><template></template></turbo-stream>"##;
+    let highlight = Highlight {
+        selector: "#message",
+        color: Some(r#""red""#),
+        r#type: "flash",
+        ignored: 1,
+    };
+    assert_eq!(expected, highlight.render());
+}
+
+#[test]
+fn optional_attribute() {
+    let expected = r##"<turbo-stream action="highlight" targets="#message" type="flash"><template></template></turbo-stream>"##;
+    let highlight = Highlight {
+        selector: "#message",
+        color: None,
+        r#type: "flash",
+        ignored: 1,
+    };
+    assert_eq!(expected, highlight.render());
+}
+
+#[test]
+fn without_fields() {
+    let expected = r#"<turbo-stream action="ping"><template></template></turbo-stream>"#;
+    assert_eq!(expected, Ping.render());
+}
+
+#[test]
+fn display() {
+    let toast = Toast {
+        level: Level::Info,
+        body: "Hello".to_string(),
+    };
+    assert_eq!(
+        format!("{}{}", stream::remove("message_1"), toast.render()),
+        format!("{}{toast}", stream::remove("message_1"))
+    );
+}
+
+#[test]
+fn in_turbo_stream_macro() {
+    let toast = Toast {
+        level: Level::Info,
+        body: "Hello".to_string(),
+    };
+    assert_eq!(
+        format!("{}{}", stream::remove("message_1"), toast.render()),
+        turbo_stream! {
+            remove "message_1";
+            action &toast;
+        }
+    );
+}
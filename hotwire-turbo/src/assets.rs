@@ -0,0 +1,294 @@
+//! Embedded JavaScript assets with digested file names, and the importmap pointing at them.
+//!
+//! The actions this crate renders only work with a client that ships them, so the builds of
+//! `@hotwired/turbo` and `turbo_power` an application targets are best embedded in the binary
+//! and served next to the markup using them. This crate doesn't bundle the builds itself;
+//! embed the ones you have vetted with `include_bytes!`:
+//!
+//! ```
+//! use hotwire_turbo::assets::{Asset, Assets};
+//!
+//! let assets = Assets::new("/assets")
+//!     .asset(Asset::new("@hotwired/turbo", "turbo.js", b"/* turbo */".as_slice()))
+//!     .asset(Asset::new("turbo_power", "turbo_power.js", b"/* turbo_power */".as_slice()));
+//!
+//! assert_eq!(
+//!     Some("/assets/turbo-7c63ea444f506593.js".to_string()),
+//!     assets.path("@hotwired/turbo")
+//! );
+//! assert_eq!(
+//!     concat!(
+//!         r#"<script type="importmap">{"imports":{"@hotwired/turbo":"/assets/turbo-7c63ea444f506593.js","turbo_power":"/assets/turbo_power-718aa774dca22731.js"},"#,
+//!         r#""integrity":{"/assets/turbo-7c63ea444f506593.js":"sha384-JWdfU7uk5cAp3hLtc/2R84Ty7l0ZT9xHChcNoPiSSSdmoCmvUp6Hux2iMcPSuzfh","#,
+//!         r#""/assets/turbo_power-718aa774dca22731.js":"sha384-Owy/duD4ilcgOwDmRX6DPbsPcEjrnzK/7xklhbpu4hlUPe8+g3/075J+kcs+xSem"}}</script>"#,
+//!     ),
+//!     assets.importmap(None)
+//! );
+//! ```
+//!
+//! The importmap and `modulepreload` links carry the Subresource Integrity hash of every asset,
+//! so browsers refuse a build that doesn't match the one embedded.
+use crate::csp::Nonce;
+use crate::escape_attribute;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha384};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// A JavaScript module, imported by its specifier and served under a digested file name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Asset {
+    specifier: Cow<'static, str>,
+    name: Cow<'static, str>,
+    content: Cow<'static, [u8]>,
+    digest: String,
+    integrity: String,
+}
+
+impl Asset {
+    pub fn new(
+        specifier: impl Into<Cow<'static, str>>,
+        name: impl Into<Cow<'static, str>>,
+        content: impl Into<Cow<'static, [u8]>>,
+    ) -> Self {
+        let content = content.into();
+        let digest = Sha256::digest(&content)[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let integrity = format!("sha384-{}", STANDARD.encode(Sha384::digest(&content)));
+        Asset {
+            specifier: specifier.into(),
+            name: name.into(),
+            content,
+            digest,
+            integrity,
+        }
+    }
+
+    /// The bare specifier the module is imported with, e.g. `@hotwired/turbo`.
+    pub fn specifier(&self) -> &str {
+        &self.specifier
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    /// The content, still borrowed when the asset was built from a `&'static [u8]`.
+    pub fn into_content(self) -> Cow<'static, [u8]> {
+        self.content
+    }
+
+    /// The first 64 bits of the SHA-256 of the content, hex encoded.
+    pub fn digest(&self) -> &str {
+        &self.digest
+    }
+
+    /// The Subresource Integrity hash of the content, e.g. `sha384-<base64>`.
+    pub fn integrity(&self) -> &str {
+        &self.integrity
+    }
+
+    /// The name with the digest inserted before the extension, e.g. `turbo-<digest>.js`.
+    pub fn digested_name(&self) -> String {
+        match self.name.rsplit_once('.') {
+            Some((stem, extension)) => format!("{stem}-{}.{extension}", self.digest),
+            None => format!("{}-{}", self.name, self.digest),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self.name.rsplit_once('.').map(|(_, extension)| extension) {
+            Some("js" | "mjs") => "text/javascript",
+            Some("css") => "text/css",
+            Some("map" | "json") => "application/json",
+            _ => "application/octet-stream",
+        }
+    }
+}
+
+/// A set of assets served below a common path prefix.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Assets {
+    prefix: String,
+    assets: Vec<Asset>,
+}
+
+impl Assets {
+    pub fn new(prefix: &str) -> Self {
+        Assets {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            assets: Vec::new(),
+        }
+    }
+
+    /// Adds the asset, replacing one with the same specifier.
+    pub fn asset(mut self, asset: Asset) -> Self {
+        self.assets
+            .retain(|existing| existing.specifier != asset.specifier);
+        self.assets.push(asset);
+        self
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Asset> {
+        self.assets.iter()
+    }
+
+    /// The digested path of the asset imported as `specifier`.
+    pub fn path(&self, specifier: &str) -> Option<String> {
+        self.assets
+            .iter()
+            .find(|asset| asset.specifier == specifier)
+            .map(|asset| self.path_of(asset))
+    }
+
+    /// The asset served as `digested_name`.
+    pub fn find(&self, digested_name: &str) -> Option<&Asset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.digested_name() == digested_name)
+    }
+
+    /// Renders the `<script type="importmap">` tag mapping every specifier to its digested path,
+    /// and every path to its integrity hash.
+    pub fn importmap(&self, nonce: Option<&Nonce>) -> String {
+        let imports: BTreeMap<&str, String> = self
+            .assets
+            .iter()
+            .map(|asset| (asset.specifier(), self.path_of(asset)))
+            .collect();
+        let integrity: BTreeMap<String, &str> = self
+            .assets
+            .iter()
+            .map(|asset| (self.path_of(asset), asset.integrity()))
+            .collect();
+        let json = serde_json::json!({ "imports": imports, "integrity": integrity })
+            .to_string()
+            .replace('<', "\\u003c");
+        match nonce {
+            Some(nonce) => format!(
+                r#"<script type="importmap" nonce="{}">{json}</script>"#,
+                escape_attribute(nonce.as_str())
+            ),
+            None => format!(r#"<script type="importmap">{json}</script>"#),
+        }
+    }
+
+    /// Renders a `<link rel="modulepreload">` tag for every asset.
+    pub fn modulepreload(&self) -> String {
+        self.assets
+            .iter()
+            .map(|asset| {
+                format!(
+                    r#"<link rel="modulepreload" href="{}" integrity="{}">"#,
+                    escape_attribute(&self.path_of(asset)),
+                    asset.integrity()
+                )
+            })
+            .collect()
+    }
+
+    fn path_of(&self, asset: &Asset) -> String {
+        format!("{}/{}", self.prefix, asset.digested_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn turbo() -> Asset {
+        Asset::new("@hotwired/turbo", "turbo.min.js", b"turbo".as_slice())
+    }
+
+    #[test]
+    fn digest() {
+        let asset = turbo();
+        assert_eq!(16, asset.digest().len());
+        assert_eq!(
+            format!("turbo.min-{}.js", asset.digest()),
+            asset.digested_name()
+        );
+        assert_ne!(
+            asset.digest(),
+            Asset::new("@hotwired/turbo", "turbo.min.js", b"turbo 8".as_slice()).digest()
+        );
+    }
+
+    #[test]
+    fn integrity() {
+        // `echo -n turbo | openssl dgst -sha384 -binary | base64`
+        assert_eq!(
+            "sha384-Wl6UP2obJtYNCRZo0W34fBavZlJ2qCVJvGFQ9asNs54elcZzcI731zugDKQcpfnj",
+            turbo().integrity()
+        );
+    }
+
+    #[test]
+    fn content_type() {
+        assert_eq!("text/javascript", turbo().content_type());
+        assert_eq!(
+            "application/json",
+            Asset::new("map", "turbo.js.map", b"".as_slice()).content_type()
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let assets = Assets::new("/assets/").asset(turbo());
+        let digested_name = turbo().digested_name();
+        assert_eq!(
+            Some(format!("/assets/{digested_name}")),
+            assets.path("@hotwired/turbo")
+        );
+        assert_eq!(None, assets.path("turbo_power"));
+        assert_eq!(Some(&turbo()), assets.find(&digested_name));
+        assert_eq!(None, assets.find("turbo.min.js"));
+    }
+
+    #[test]
+    fn same_specifier_is_replaced() {
+        let newer = Asset::new("@hotwired/turbo", "turbo.js", b"turbo 8".as_slice());
+        let assets = Assets::new("/assets").asset(turbo()).asset(newer.clone());
+        assert_eq!(vec![&newer], assets.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn importmap_with_nonce() {
+        let assets = Assets::new("/assets").asset(turbo());
+        let expected = format!(
+            r#"<script type="importmap" nonce="abc123">{{"imports":{{"@hotwired/turbo":"/assets/{0}"}},"integrity":{{"/assets/{0}":"{1}"}}}}</script>"#,
+            turbo().digested_name(),
+            turbo().integrity()
+        );
+        assert_eq!(expected, assets.importmap(Some(&Nonce::new("abc123"))));
+    }
+
+    #[test]
+    fn importmap_is_escaped() {
+        let assets = Assets::new("/assets").asset(Asset::new("</script>", "x.js", b"".as_slice()));
+        assert_eq!(1, assets.importmap(None).matches("</script>").count());
+    }
+
+    #[test]
+    fn modulepreload() {
+        let assets = Assets::new("/assets").asset(turbo());
+        let expected = format!(
+            r#"<link rel="modulepreload" href="/assets/{}" integrity="{}">"#,
+            turbo().digested_name(),
+            turbo().integrity()
+        );
+        assert_eq!(expected, assets.modulepreload());
+    }
+}
@@ -0,0 +1,190 @@
+//! [TurboBoost Streams](https://github.com/hopsoft/turbo_boost-streams) `invoke` actions, which
+//! call DOM methods on the client.
+//!
+//! ```
+//! use hotwire_turbo::boost::{invoke, Receiver};
+//! use hotwire_turbo::TurboAction;
+//!
+//! let payload = invoke(Receiver::selector("#dialog"), "showModal").render();
+//! assert_eq!(
+//!     r##"<turbo-stream action="invoke" target="DOM"><template>{"args":[],"method":"showModal","selector":"#dialog"}</template></turbo-stream>"##,
+//!     payload
+//! );
+//!
+//! let payload = invoke(Receiver::Document, "dispatchEvent")
+//!     .arg(serde_json::json!({ "type": "saved" }))
+//!     .delay(250)
+//!     .render();
+//! ```
+use crate::{turbo_stream_target, TurboAction};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// The object a method is called on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Receiver {
+    Window,
+    Document,
+    /// Every element matching the CSS selector.
+    Selector(String),
+}
+
+impl Receiver {
+    pub fn selector(selector: &str) -> Self {
+        Receiver::Selector(selector.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Call {
+    method: String,
+    args: Vec<Value>,
+}
+
+/// One or more method calls on a receiver, rendered as one `invoke` action per call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invoke {
+    receiver: Receiver,
+    calls: Vec<Call>,
+    delay: Option<u64>,
+}
+
+/// Calls `method` on `receiver`. Dotted methods such as `classList.add` are resolved on the
+/// receiver by the client.
+pub fn invoke(receiver: Receiver, method: &str) -> Invoke {
+    Invoke {
+        receiver,
+        calls: vec![Call {
+            method: method.to_string(),
+            args: Vec::new(),
+        }],
+        delay: None,
+    }
+}
+
+impl Invoke {
+    /// Appends an argument to the current call, serialized as JSON.
+    pub fn arg(mut self, arg: impl Into<Value>) -> Self {
+        self.calls
+            .last_mut()
+            .expect("an invoke has at least one call")
+            .args
+            .push(arg.into());
+        self
+    }
+
+    /// Chains another call on the same receiver, made after the previous one.
+    pub fn then(mut self, method: &str) -> Self {
+        self.calls.push(Call {
+            method: method.to_string(),
+            args: Vec::new(),
+        });
+        self
+    }
+
+    /// Waits `milliseconds` before each call. Every call carries the delay, so the client still
+    /// makes them in order.
+    pub fn delay(mut self, milliseconds: u64) -> Self {
+        self.delay = Some(milliseconds);
+        self
+    }
+
+    fn payload(&self, call: &Call) -> String {
+        let mut payload = Map::new();
+        match &self.receiver {
+            Receiver::Window => payload.insert("receiver".into(), "window".into()),
+            Receiver::Document => payload.insert("receiver".into(), "document".into()),
+            Receiver::Selector(selector) => {
+                payload.insert("selector".into(), selector.as_str().into())
+            }
+        };
+        payload.insert("method".into(), call.method.as_str().into());
+        payload.insert("args".into(), call.args.clone().into());
+        if let Some(delay) = self.delay {
+            payload.insert("delay".into(), delay.into());
+        }
+        // Keeps the JSON from closing the template or starting markup inside it.
+        Value::Object(payload)
+            .to_string()
+            .replace('<', "\\u003c")
+            .replace('>', "\\u003e")
+            .replace('&', "\\u0026")
+    }
+}
+
+impl TurboAction for Invoke {
+    fn render(&self) -> String {
+        self.calls
+            .iter()
+            .map(|call| {
+                turbo_stream_target("invoke", "DOM", &mut Default::default(), self.payload(call))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Invoke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&TurboAction::render(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turbo_stream;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn window() {
+        let expected = r#"<turbo-stream action="invoke" target="DOM"><template>{"args":["Hello",1,true],"method":"console.log","receiver":"window"}</template></turbo-stream>"#;
+        let invoke = invoke(Receiver::Window, "console.log")
+            .arg("Hello")
+            .arg(1)
+            .arg(true);
+        assert_eq!(expected, invoke.render());
+    }
+
+    #[test]
+    fn document_with_delay() {
+        let expected = r#"<turbo-stream action="invoke" target="DOM"><template>{"args":[{"detail":{"id":1},"type":"saved"}],"delay":500,"method":"dispatchEvent","receiver":"document"}</template></turbo-stream>"#;
+        let invoke = invoke(Receiver::Document, "dispatchEvent")
+            .arg(json!({ "type": "saved", "detail": { "id": 1 } }))
+            .delay(500);
+        assert_eq!(expected, invoke.to_string());
+    }
+
+    #[test]
+    fn chained_calls() {
+        let expected = concat!(
+            r##"<turbo-stream action="invoke" target="DOM"><template>{"args":["open"],"delay":100,"method":"classList.add","selector":"#menu"}</template></turbo-stream>"##,
+            r##"<turbo-stream action="invoke" target="DOM"><template>{"args":[],"delay":100,"method":"focus","selector":"#menu"}</template></turbo-stream>"##,
+        );
+        let invoke = invoke(Receiver::selector("#menu"), "classList.add")
+            .arg("open")
+            .then("focus")
+            .delay(100);
+        assert_eq!(expected, invoke.render());
+    }
+
+    #[test]
+    fn markup_in_arguments_is_escaped() {
+        let expected = r#"<turbo-stream action="invoke" target="DOM"><template>{"args":["\u003c/template\u003e \u0026"],"method":"insertAdjacentText","selector":"p"}</template></turbo-stream>"#;
+        let invoke = invoke(Receiver::selector("p"), "insertAdjacentText").arg("</template> &");
+        assert_eq!(expected, invoke.render());
+    }
+
+    #[test]
+    fn in_turbo_stream_macro() {
+        let focus = invoke(Receiver::selector("#input"), "focus");
+        let payload = turbo_stream! {
+            remove "flash";
+            action &focus;
+        };
+        assert_eq!(
+            format!("{}{}", crate::stream::remove("flash"), focus.render()),
+            payload
+        );
+    }
+}
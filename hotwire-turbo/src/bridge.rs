@@ -0,0 +1,398 @@
+//! [Hotwire Native](https://native.hotwired.dev) bridge components, formerly Strada: the
+//! `bridge--*` controller markup and the messages exchanged with the native app.
+//!
+//! ```
+//! use hotwire_turbo::bridge::{self, Menu};
+//! use hotwire_turbo::stream;
+//!
+//! let menu = Menu::new("Sort by").item("Newest").item("Oldest");
+//! let markup = bridge::element("div", "sort", &menu, "").unwrap();
+//! assert_eq!(
+//!     r#"<div id="sort" data-bridge--menu-items-value="[{&quot;index&quot;:0,&quot;title&quot;:&quot;Newest&quot;},{&quot;index&quot;:1,&quot;title&quot;:&quot;Oldest&quot;}]" data-bridge--menu-title-value="Sort by" data-controller="bridge--menu"></div>"#,
+//!     markup
+//! );
+//!
+//! // Updates the native menu along with the page.
+//! let payload = stream::replace("sort", markup);
+//! ```
+//!
+//! Components are rendered with their data as Stimulus values, which the `bridge--*` controllers
+//! send to the app in the [`Message`] returned by [`Component::message`].
+use crate::escape_attribute;
+use crate::stimulus::{Attributes, Controller};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The message isn't valid JSON or misses `component` or `event`.
+    InvalidMessage(String),
+    /// The component name doesn't make a valid `bridge--[name]` controller identifier.
+    InvalidName(&'static str),
+    /// A key of the component data isn't a valid Stimulus value name.
+    InvalidDataKey(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidMessage(reason) => write!(f, "invalid bridge message: {reason}"),
+            Error::InvalidName(name) => write!(f, "invalid bridge component name `{name}`"),
+            Error::InvalidDataKey(key) => write!(f, "invalid bridge component data key `{key}`"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A message between a bridge component and the native app.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    component: String,
+    event: String,
+    data: Value,
+}
+
+impl Message {
+    pub fn new(component: &str, event: &str, data: impl Into<Value>) -> Self {
+        Message {
+            component: component.to_string(),
+            event: event.to_string(),
+            data: data.into(),
+        }
+    }
+
+    /// Parses a message as sent over the bridge, e.g. a reply from the app.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let message: Map<String, Value> =
+            serde_json::from_str(json).map_err(|error| Error::InvalidMessage(error.to_string()))?;
+        let string = |key: &str| {
+            message
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| Error::InvalidMessage(format!("missing `{key}`")))
+        };
+        Ok(Message {
+            component: string("component")?,
+            event: string("event")?,
+            data: message.get("data").cloned().unwrap_or(json!({})),
+        })
+    }
+
+    /// The component name, e.g. `menu`.
+    pub fn component(&self) -> &str {
+        &self.component
+    }
+
+    /// The event, e.g. `connect` or `submitEnabled`.
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    pub fn data(&self) -> &Value {
+        &self.data
+    }
+
+    pub fn to_json(&self) -> String {
+        json!({
+            "component": self.component,
+            "event": self.event,
+            "data": self.data,
+        })
+        .to_string()
+    }
+}
+
+/// A bridge component rendered by the web view and mirrored by a native control.
+pub trait Component {
+    /// The component name, shared with the native app.
+    fn name(&self) -> &'static str;
+
+    /// The message data the controller sends when it connects.
+    fn data(&self) -> Value;
+
+    /// The `bridge--[name]` Stimulus controller, failing when the name is no valid identifier.
+    fn controller(&self) -> Result<Controller, Error> {
+        Controller::new(&format!("bridge--{}", self.name()))
+            .map_err(|_| Error::InvalidName(self.name()))
+    }
+
+    /// `data-controller` plus every field of [`Component::data`] as a Stimulus value.
+    fn attributes(&self) -> Result<Attributes, Error> {
+        let controller = self.controller()?;
+        let attributes = Attributes::new().controller(&controller);
+        match self.data() {
+            Value::Object(data) => {
+                data.into_iter()
+                    .try_fold(attributes, |attributes, (name, value)| {
+                        attributes
+                            .value(&controller, &name, value)
+                            .map_err(|_| Error::InvalidDataKey(name))
+                    })
+            }
+            _ => Ok(attributes),
+        }
+    }
+
+    /// The `connect` message the controller sends to the app.
+    fn message(&self) -> Message {
+        Message::new(self.name(), "connect", self.data())
+    }
+}
+
+/// Renders `<tag id="id" [attributes]>content</tag>` for the component.
+pub fn element<C: Component + ?Sized>(
+    tag: &str,
+    id: &str,
+    component: &C,
+    content: &str,
+) -> Result<String, Error> {
+    Ok(format!(
+        r#"<{tag} id="{}" {}>{content}</{tag}>"#,
+        escape_attribute(id),
+        component.attributes()?
+    ))
+}
+
+/// A native navigation bar button.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Button {
+    title: String,
+    image_name: Option<String>,
+}
+
+impl Button {
+    pub fn new(title: &str) -> Self {
+        Button {
+            title: title.to_string(),
+            image_name: None,
+        }
+    }
+
+    /// A platform image shown instead of the title, e.g. an SF Symbol name.
+    pub fn image_name(mut self, image_name: &str) -> Self {
+        self.image_name = Some(image_name.to_string());
+        self
+    }
+}
+
+impl Component for Button {
+    fn name(&self) -> &'static str {
+        "button"
+    }
+
+    fn data(&self) -> Value {
+        let mut data = json!({ "title": self.title });
+        if let Some(image_name) = &self.image_name {
+            data["imageName"] = image_name.as_str().into();
+        }
+        data
+    }
+}
+
+/// A native form submit button, replacing the web one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Form {
+    submit_title: String,
+}
+
+impl Form {
+    pub fn new(submit_title: &str) -> Self {
+        Form {
+            submit_title: submit_title.to_string(),
+        }
+    }
+
+    /// The message enabling or disabling the native submit button, e.g. while submitting.
+    pub fn submit_enabled(&self, enabled: bool) -> Message {
+        let event = if enabled {
+            "submitEnabled"
+        } else {
+            "submitDisabled"
+        };
+        Message::new(self.name(), event, json!({}))
+    }
+}
+
+impl Component for Form {
+    fn name(&self) -> &'static str {
+        "form"
+    }
+
+    fn data(&self) -> Value {
+        json!({ "submitTitle": self.submit_title })
+    }
+}
+
+/// A native menu, replying with the index of the selected item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Menu {
+    title: String,
+    items: Vec<String>,
+}
+
+impl Menu {
+    pub fn new(title: &str) -> Self {
+        Menu {
+            title: title.to_string(),
+            items: Vec::new(),
+        }
+    }
+
+    pub fn item(mut self, title: &str) -> Self {
+        self.items.push(title.to_string());
+        self
+    }
+
+    /// The index of the item selected in the app's reply.
+    pub fn selected_index(reply: &Message) -> Option<usize> {
+        reply
+            .data()
+            .get("selectedIndex")
+            .and_then(Value::as_u64)
+            .map(|index| index as usize)
+    }
+}
+
+impl Component for Menu {
+    fn name(&self) -> &'static str {
+        "menu"
+    }
+
+    fn data(&self) -> Value {
+        let items: Vec<Value> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, title)| json!({ "title": title, "index": index }))
+            .collect();
+        json!({ "title": self.title, "items": items })
+    }
+}
+
+/// A native overflow menu item, clicking the web element when selected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverflowMenu {
+    label: String,
+}
+
+impl OverflowMenu {
+    pub fn new(label: &str) -> Self {
+        OverflowMenu {
+            label: label.to_string(),
+        }
+    }
+}
+
+impl Component for OverflowMenu {
+    fn name(&self) -> &'static str {
+        "overflow-menu"
+    }
+
+    fn data(&self) -> Value {
+        json!({ "label": self.label })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn button() {
+        let button = Button::new("Edit").image_name("pencil");
+        assert_eq!(
+            r#"data-bridge--button-image-name-value="pencil" data-bridge--button-title-value="Edit" data-controller="bridge--button""#,
+            button.attributes().unwrap().to_string()
+        );
+        assert_eq!(
+            r#"{"component":"button","data":{"imageName":"pencil","title":"Edit"},"event":"connect"}"#,
+            button.message().to_json()
+        );
+    }
+
+    #[test]
+    fn form() {
+        let form = Form::new("Save");
+        assert_eq!(
+            r#"<form id="edit_post" data-bridge--form-submit-title-value="Save" data-controller="bridge--form"></form>"#,
+            element("form", "edit_post", &form, "").unwrap()
+        );
+        assert_eq!(
+            r#"{"component":"form","data":{},"event":"submitDisabled"}"#,
+            form.submit_enabled(false).to_json()
+        );
+    }
+
+    #[test]
+    fn menu_reply() {
+        let reply = Message::from_json(
+            r#"{"component":"menu","event":"connect","data":{"selectedIndex":1}}"#,
+        )
+        .unwrap();
+        assert_eq!("menu", reply.component());
+        assert_eq!("connect", reply.event());
+        assert_eq!(Some(1), Menu::selected_index(&reply));
+    }
+
+    #[test]
+    fn overflow_menu() {
+        assert_eq!(
+            r#"data-bridge--overflow-menu-label-value="Share" data-controller="bridge--overflow-menu""#,
+            OverflowMenu::new("Share").attributes().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_message() {
+        assert_eq!(
+            Err(Error::InvalidMessage("missing `event`".to_string())),
+            Message::from_json(r#"{"component":"menu"}"#)
+        );
+    }
+
+    #[test]
+    fn invalid_name() {
+        struct Bad;
+
+        impl Component for Bad {
+            fn name(&self) -> &'static str {
+                "Sort Menu"
+            }
+
+            fn data(&self) -> Value {
+                json!({})
+            }
+        }
+
+        assert_eq!(Err(Error::InvalidName("Sort Menu")), Bad.controller());
+        assert_eq!(
+            "invalid bridge component name `Sort Menu`",
+            element("div", "sort", &Bad, "").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_data_key() {
+        struct Bad;
+
+        impl Component for Bad {
+            fn name(&self) -> &'static str {
+                "menu"
+            }
+
+            fn data(&self) -> Value {
+                json!({ "title\" onclick=\"alert(1)": "Sort" })
+            }
+        }
+
+        assert_eq!(
+            Err(Error::InvalidDataKey(
+                r#"title" onclick="alert(1)"#.to_string()
+            )),
+            Bad.attributes()
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Renders stream payloads as [CableReady](https://cableready.stimulusreflex.com) operations, so
+//! one server can drive both a CableReady and a Turbo Power client.
+//!
+//! ```
+//! use hotwire_turbo::{cable_ready, power, stream};
+//!
+//! let payload = stream::update("count", "42") + &power::add_css_class(".item", "active done");
+//! assert_eq!(
+//!     r##"[{"html":"42","operation":"innerHtml","selector":"#count"},{"name":["active","done"],"operation":"addCssClass","selectAll":true,"selector":".item"}]"##,
+//!     cable_ready::render(&payload)?
+//! );
+//! # Ok::<(), hotwire_turbo::cable_ready::Error>(())
+//! ```
+//!
+//! Actions without a CableReady operation, such as the `turbo_*` ones, fail with
+//! [`Error::NoEquivalent`]; [`operation_name`] tells them apart up front.
+use crate::parse::{self, Stream};
+use serde_json::{Map, Value};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(parse::Error),
+    /// The action has no CableReady operation.
+    NoEquivalent(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => error.fmt(f),
+            Error::NoEquivalent(action) => {
+                write!(f, "`{action}` has no CableReady equivalent")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Self {
+        Error::Parse(error)
+    }
+}
+
+/// Every action of the [`registry`](crate::registry) and the CableReady operation it maps to,
+/// `None` when there is none.
+#[rustfmt::skip]
+pub const OPERATIONS: &[(&str, Option<&str>)] = &[
+    ("after", Some("insertAdjacentHtml")),
+    ("append", Some("append")),
+    ("before", Some("insertAdjacentHtml")),
+    ("prepend", Some("prepend")),
+    ("refresh", None),
+    ("remove", Some("remove")),
+    ("replace", Some("outerHtml")),
+    ("update", Some("innerHtml")),
+    ("add_css_class", Some("addCssClass")),
+    ("clear_storage", Some("clearStorage")),
+    ("console_log", Some("consoleLog")),
+    ("console_table", Some("consoleTable")),
+    ("dispatch_event", Some("dispatchEvent")),
+    ("graft", Some("graft")),
+    ("history_back", Some("historyBack")),
+    ("history_forward", Some("historyForward")),
+    ("history_go", Some("historyGo")),
+    ("inner_html", Some("innerHtml")),
+    ("insert_adjacent_html", Some("insertAdjacentHtml")),
+    ("insert_adjacent_text", Some("insertAdjacentText")),
+    ("invoke", None),
+    ("morph", Some("morph")),
+    ("notification", Some("notification")),
+    ("outer_html", Some("outerHtml")),
+    ("push_state", Some("pushState")),
+    ("redirect_to", Some("redirectTo")),
+    ("reload", Some("reload")),
+    ("remove_attribute", Some("removeAttribute")),
+    ("remove_css_class", Some("removeCssClass")),
+    ("remove_storage_item", Some("removeStorageItem")),
+    ("replace_css_class", Some("replaceCssClass")),
+    ("replace_state", Some("replaceState")),
+    ("reset_form", None),
+    ("scroll_into_view", Some("scrollIntoView")),
+    ("set_attribute", Some("setAttribute")),
+    ("set_cookie", Some("setCookie")),
+    ("set_cookie_item", None),
+    ("set_dataset_attribute", Some("setDatasetProperty")),
+    ("set_focus", Some("setFocus")),
+    ("set_meta", Some("setMeta")),
+    ("set_property", Some("setProperty")),
+    ("set_storage_item", Some("setStorageItem")),
+    ("set_style", Some("setStyle")),
+    ("set_styles", Some("setStyles")),
+    ("set_title", Some("setTitle")),
+    ("set_value", Some("setValue")),
+    ("text_content", Some("textContent")),
+    ("toggle_attribute", None),
+    ("toggle_css_class", None),
+    ("turbo_clear_cache", None),
+    ("turbo_frame_reload", None),
+    ("turbo_frame_set_src", None),
+    ("turbo_progress_bar_hide", None),
+    ("turbo_progress_bar_set_value", None),
+    ("turbo_progress_bar_show", None),
+];
+
+/// The CableReady operation for `action`, `None` for actions without one or unknown actions.
+pub fn operation_name(action: &str) -> Option<&'static str> {
+    OPERATIONS
+        .iter()
+        .find(|(name, _)| *name == action)
+        .and_then(|(_, operation)| *operation)
+}
+
+/// Renders the payload as a JSON array of CableReady operations.
+pub fn render(payload: &str) -> Result<String, Error> {
+    Ok(Value::Array(operations(payload)?).to_string())
+}
+
+/// Converts every `<turbo-stream>` element of the payload to a CableReady operation.
+pub fn operations(payload: &str) -> Result<Vec<Value>, Error> {
+    parse::parse(payload)?.iter().map(operation).collect()
+}
+
+/// Converts one stream to a CableReady operation.
+pub fn operation(stream: &Stream) -> Result<Value, Error> {
+    let action = stream.action();
+    let name = operation_name(action).ok_or_else(|| Error::NoEquivalent(action.to_string()))?;
+    let mut operation = Map::new();
+    operation.insert("operation".into(), name.into());
+    if let Some(target) = stream.attribute("target") {
+        operation.insert("selector".into(), format!("#{}", css_escape(target)).into());
+    } else if let Some(targets) = stream.attribute("targets") {
+        operation.insert("selector".into(), targets.into());
+        operation.insert("selectAll".into(), true.into());
+    }
+    for (attribute, value) in stream.attributes() {
+        if matches!(attribute.as_str(), "action" | "target" | "targets") {
+            continue;
+        }
+        let (key, value) = match (action, attribute.as_str()) {
+            (_, "attribute") => ("name".to_string(), value.as_str().into()),
+            (_, "classes") => ("name".to_string(), classes(value)),
+            ("set_styles", "styles") => ("styles".to_string(), styles(value)),
+            ("history_go", "delta") => (
+                "delta".to_string(),
+                value
+                    .parse::<i64>()
+                    .map_or_else(|_| value.as_str().into(), Value::from),
+            ),
+            ("redirect_to", "turbo-action") => ("action".to_string(), value.as_str().into()),
+            ("console_table" | "push_state" | "replace_state", "data" | "columns" | "state") => {
+                (camel_case(attribute), json(value))
+            }
+            _ => (camel_case(attribute), value.as_str().into()),
+        };
+        operation.insert(key, value);
+    }
+    let content = stream.content();
+    match action {
+        "before" => {
+            operation.insert("position".into(), "beforebegin".into());
+            operation.insert("html".into(), content.into());
+        }
+        "after" => {
+            operation.insert("position".into(), "afterend".into());
+            operation.insert("html".into(), content.into());
+        }
+        "dispatch_event" => {
+            operation.insert("detail".into(), json(content));
+        }
+        "notification" => {
+            let options: Map<String, Value> = operation
+                .iter()
+                .filter(|(key, _)| !matches!(key.as_str(), "operation" | "title"))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            operation.retain(|key, _| matches!(key.as_str(), "operation" | "title"));
+            operation.insert("options".into(), options.into());
+        }
+        _ if !content.is_empty() => {
+            operation.insert("html".into(), content.into());
+        }
+        _ => {}
+    }
+    Ok(Value::Object(operation))
+}
+
+fn camel_case(name: &str) -> String {
+    let mut output = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '-' | '_' => upper = true,
+            c if upper => {
+                output.push(c.to_ascii_uppercase());
+                upper = false;
+            }
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+fn classes(value: &str) -> Value {
+    value.split_whitespace().collect::<Vec<_>>().into()
+}
+
+/// `color: red; margin: 0` as `{"color": "red", "margin": "0"}`.
+fn styles(value: &str) -> Value {
+    let styles: Map<String, Value> = value
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().into()))
+        .collect();
+    styles.into()
+}
+
+/// Parses JSON values, keeping anything else as a string.
+fn json(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| value.into())
+}
+
+/// Escapes an element id for use in an id selector.
+pub(crate) fn css_escape(id: &str) -> String {
+    let mut output = String::with_capacity(id.len());
+    for (index, c) in id.chars().enumerate() {
+        let leading_digit = index == 0 && c.is_ascii_digit();
+        if leading_digit {
+            output.push_str(&format!("\\{:x} ", c as u32));
+        } else if c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii() {
+            output.push(c);
+        } else {
+            output.push('\\');
+            output.push(c);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{power, registry, stream};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn only(payload: &str) -> Value {
+        operations(payload).unwrap().remove(0)
+    }
+
+    #[test]
+    fn every_registry_action_is_mapped() {
+        for action in registry::ACTIONS {
+            assert!(
+                OPERATIONS.iter().any(|(name, _)| *name == action.name),
+                "{}",
+                action.name
+            );
+        }
+        assert_eq!(registry::ACTIONS.len(), OPERATIONS.len());
+    }
+
+    #[test]
+    fn turbo_actions() {
+        assert_eq!(
+            json!({"operation": "insertAdjacentHtml", "selector": "#list", "position": "beforebegin", "html": "<p>1</p>"}),
+            only(&stream::before("list", "<p>1</p>"))
+        );
+        assert_eq!(
+            json!({"operation": "append", "selector": ".list", "selectAll": true, "html": "<li>1</li>"}),
+            only(&stream::append_all(".list", "<li>1</li>"))
+        );
+        assert_eq!(
+            json!({"operation": "remove", "selector": "#item_1"}),
+            only(&stream::remove("item_1"))
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(
+            json!({"operation": "setAttribute", "selector": "#form", "selectAll": true, "name": "disabled", "value": ""}),
+            only(&power::set_attribute("#form", "disabled", ""))
+        );
+        assert_eq!(
+            json!({"operation": "setStyles", "selector": "p", "selectAll": true, "styles": {"color": "red", "margin": "0"}}),
+            only(&power::set_styles("p", "color: red; margin: 0;"))
+        );
+        assert_eq!(
+            json!({"operation": "redirectTo", "url": "/inbox", "action": "replace", "turboFrame": "main"}),
+            only(&power::redirect_to("/inbox", "replace", Some("main")))
+        );
+        assert_eq!(
+            json!({"operation": "historyGo", "delta": 2}),
+            only(&power::history_go(2))
+        );
+    }
+
+    #[test]
+    fn dispatch_event() {
+        assert_eq!(
+            json!({"operation": "dispatchEvent", "selector": "#el", "selectAll": true, "name": "saved", "detail": {"id": 1}}),
+            only(&power::dispatch_event("#el", "saved", r#"{"id":1}"#))
+        );
+    }
+
+    #[test]
+    fn notification() {
+        let payload = power::notification(
+            "Saved",
+            &mut BTreeMap::from([("body", "All good"), ("icon", "/ok.png")]),
+            "",
+        );
+        assert_eq!(
+            json!({"operation": "notification", "title": "Saved", "options": {"body": "All good", "icon": "/ok.png"}}),
+            only(&payload)
+        );
+    }
+
+    #[test]
+    fn no_equivalent() {
+        let payload = stream::remove("flash") + &power::turbo_frame_reload("comments");
+        assert_eq!(
+            Err(Error::NoEquivalent("turbo_frame_reload".to_string())),
+            render(&payload)
+        );
+        assert_eq!(None, operation_name("confetti"));
+    }
+
+    #[test]
+    fn ids_are_escaped() {
+        assert_eq!(
+            json!({"operation": "remove", "selector": "#\\31 23\\.a"}),
+            only(&stream::remove("123.a"))
+        );
+    }
+}
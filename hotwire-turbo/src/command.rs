@@ -0,0 +1,321 @@
+//! Server side of [TurboBoost Commands](https://github.com/hopsoft/turbo_boost-commands).
+//!
+//! The client posts every command invocation as JSON to [`PATH`]. Parse the body into a
+//! [`Request`] and let [`Commands`] call the handler registered for its name, which answers with
+//! stream actions:
+//!
+//! ```
+//! use hotwire_turbo::command::{Commands, Request};
+//! use hotwire_turbo::stream;
+//!
+//! let commands = Commands::new().command("CounterCommand#increment", |request| {
+//!     let count: u32 = request.element_attribute("data-count").unwrap_or("0").parse().unwrap_or(0);
+//!     Ok(stream::update("counter", (count + 1).to_string()))
+//! });
+//!
+//! let request = Request::from_json(
+//!     r#"{"id":"1","name":"CounterCommand#increment","driver":"method","elementAttributes":{"data-count":"41"}}"#,
+//! )?;
+//! assert_eq!(stream::update("counter", "42"), commands.dispatch(&request)?);
+//! # Ok::<(), hotwire_turbo::command::Error>(())
+//! ```
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// The path the TurboBoost client posts invocations to.
+pub const PATH: &str = "/turbo-boost-command-invocation";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The request body isn't a valid invocation.
+    InvalidRequest(String),
+    /// No handler is registered for the command.
+    UnknownCommand(String),
+    /// The CSRF token is missing or doesn't verify.
+    InvalidToken,
+    /// The handler failed.
+    Failed(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidRequest(reason) => write!(f, "invalid command request: {reason}"),
+            Error::UnknownCommand(name) => write!(f, "unknown command `{name}`"),
+            Error::InvalidToken => f.write_str("invalid CSRF token"),
+            Error::Failed(reason) => write!(f, "command failed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// How the client triggered the command, which determines what it does with the response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Driver {
+    /// A form submission.
+    Form,
+    /// A link or form targeting a Turbo Frame.
+    Frame,
+    /// A `fetch` request made by the client.
+    Method,
+    /// A full page visit.
+    Window,
+}
+
+impl Driver {
+    fn parse(driver: &str) -> Option<Self> {
+        match driver {
+            "form" => Some(Driver::Form),
+            "frame" => Some(Driver::Frame),
+            "method" => Some(Driver::Method),
+            "window" => Some(Driver::Window),
+            _ => None,
+        }
+    }
+}
+
+/// A command invocation sent by the client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    id: String,
+    name: String,
+    driver: Driver,
+    element_id: Option<String>,
+    element_attributes: BTreeMap<String, String>,
+    frame_id: Option<String>,
+    src: Option<String>,
+    client_state: Map<String, Value>,
+    changed_state: Map<String, Value>,
+    signed_state: Option<String>,
+    csrf_token: Option<String>,
+}
+
+impl Request {
+    /// Parses the JSON body posted by the client.
+    pub fn from_json(body: &str) -> Result<Self, Error> {
+        let body: Map<String, Value> =
+            serde_json::from_str(body).map_err(|error| Error::InvalidRequest(error.to_string()))?;
+        let string = |key: &str| body.get(key).and_then(Value::as_str).map(str::to_string);
+        let object = |key: &str| match body.get(key) {
+            Some(Value::Object(object)) => object.clone(),
+            _ => Map::new(),
+        };
+        let required = |key: &str| {
+            string(key).ok_or_else(|| Error::InvalidRequest(format!("missing `{key}`")))
+        };
+        let driver = required("driver")?;
+        let element_attributes = object("elementAttributes")
+            .into_iter()
+            .map(|(name, value)| match value {
+                Value::String(value) => (name, value),
+                value => (name, value.to_string()),
+            })
+            .collect();
+        Ok(Request {
+            id: required("id")?,
+            name: required("name")?,
+            driver: Driver::parse(&driver)
+                .ok_or_else(|| Error::InvalidRequest(format!("unknown driver `{driver}`")))?,
+            element_id: string("elementId"),
+            element_attributes,
+            frame_id: string("frameId"),
+            src: string("src"),
+            client_state: object("clientState"),
+            changed_state: object("changedState"),
+            signed_state: string("signedState"),
+            csrf_token: string("csrfToken"),
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The command name, `ClassName#method`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn driver(&self) -> Driver {
+        self.driver
+    }
+
+    /// The id of the element that triggered the command.
+    pub fn element_id(&self) -> Option<&str> {
+        self.element_id.as_deref()
+    }
+
+    /// An attribute of the element that triggered the command, e.g. `data-count`.
+    pub fn element_attribute(&self, name: &str) -> Option<&str> {
+        self.element_attributes.get(name).map(String::as_str)
+    }
+
+    pub fn element_attributes(&self) -> &BTreeMap<String, String> {
+        &self.element_attributes
+    }
+
+    /// The frame targeted by the `frame` driver.
+    pub fn frame_id(&self) -> Option<&str> {
+        self.frame_id.as_deref()
+    }
+
+    /// The URL the `frame` and `window` drivers are loading.
+    pub fn src(&self) -> Option<&str> {
+        self.src.as_deref()
+    }
+
+    /// The full client side state.
+    pub fn client_state(&self) -> &Map<String, Value> {
+        &self.client_state
+    }
+
+    /// The client side state changed since the last command.
+    pub fn changed_state(&self) -> &Map<String, Value> {
+        &self.changed_state
+    }
+
+    /// The server signed state, as sent back by the client. Verifying it is up to the
+    /// application.
+    pub fn signed_state(&self) -> Option<&str> {
+        self.signed_state.as_deref()
+    }
+
+    /// The CSRF token the client read from the page's `csrf-token` meta tag. Verifying it is up
+    /// to the application.
+    pub fn csrf_token(&self) -> Option<&str> {
+        self.csrf_token.as_deref()
+    }
+}
+
+type Handler = Box<dyn Fn(&Request) -> Result<String, Error> + Send + Sync>;
+
+/// Command handlers by name.
+#[derive(Default)]
+pub struct Commands {
+    handlers: HashMap<String, Handler>,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the handler for `name`, replacing any previous one. The handler returns the
+    /// stream actions to respond with.
+    pub fn command<F>(mut self, name: &str, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Result<String, Error> + Send + Sync + 'static,
+    {
+        self.handlers.insert(name.to_string(), Box::new(handler));
+        self
+    }
+
+    /// Calls the handler registered for the request's command.
+    pub fn dispatch(&self, request: &Request) -> Result<String, Error> {
+        let handler = self
+            .handlers
+            .get(request.name())
+            .ok_or_else(|| Error::UnknownCommand(request.name().to_string()))?;
+        handler(request)
+    }
+
+    /// The names of the registered commands, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+}
+
+impl fmt::Debug for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Commands")
+            .field("handlers", &self.handlers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{power, stream};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const BODY: &str = r##"{
+        "id": "a1b2",
+        "name": "TodoCommand#toggle",
+        "driver": "frame",
+        "elementId": "todo_1",
+        "elementAttributes": {"data-id": "1", "data-done": true},
+        "frameId": "todos",
+        "src": "/todos",
+        "clientState": {"filter": "all", "page": 2},
+        "changedState": {"page": 2},
+        "signedState": "c2lnbmVk",
+        "csrfToken": "dG9rZW4"
+    }"##;
+
+    #[test]
+    fn parse_request() {
+        let request = Request::from_json(BODY).unwrap();
+        assert_eq!("a1b2", request.id());
+        assert_eq!("TodoCommand#toggle", request.name());
+        assert_eq!(Driver::Frame, request.driver());
+        assert_eq!(Some("todo_1"), request.element_id());
+        assert_eq!(Some("1"), request.element_attribute("data-id"));
+        assert_eq!(Some("true"), request.element_attribute("data-done"));
+        assert_eq!(Some("todos"), request.frame_id());
+        assert_eq!(Some("/todos"), request.src());
+        assert_eq!(Some(&json!(2)), request.client_state().get("page"));
+        assert_eq!(1, request.changed_state().len());
+        assert_eq!(Some("c2lnbmVk"), request.signed_state());
+        assert_eq!(Some("dG9rZW4"), request.csrf_token());
+    }
+
+    #[test]
+    fn invalid_requests() {
+        assert!(matches!(
+            Request::from_json("not json"),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert_eq!(
+            Err(Error::InvalidRequest("missing `name`".to_string())),
+            Request::from_json(r#"{"id":"1","driver":"method"}"#)
+        );
+        assert_eq!(
+            Err(Error::InvalidRequest("unknown driver `socket`".to_string())),
+            Request::from_json(r#"{"id":"1","name":"A#b","driver":"socket"}"#)
+        );
+    }
+
+    #[test]
+    fn dispatch() {
+        let commands = Commands::new()
+            .command("TodoCommand#toggle", |request| {
+                let id = request.element_attribute("data-id").unwrap_or_default();
+                Ok(power::toggle_css_class(&format!("#todo_{id}"), "done"))
+            })
+            .command("TodoCommand#destroy", |request| {
+                Ok(stream::remove(request.element_id().unwrap_or_default()))
+            });
+        let request = Request::from_json(BODY).unwrap();
+
+        assert_eq!(
+            Ok(power::toggle_css_class("#todo_1", "done")),
+            commands.dispatch(&request)
+        );
+        let mut names: Vec<_> = commands.names().collect();
+        names.sort();
+        assert_eq!(vec!["TodoCommand#destroy", "TodoCommand#toggle"], names);
+    }
+
+    #[test]
+    fn unknown_command() {
+        let request = Request::from_json(BODY).unwrap();
+        assert_eq!(
+            Err(Error::UnknownCommand("TodoCommand#toggle".to_string())),
+            Commands::new().dispatch(&request)
+        );
+    }
+}
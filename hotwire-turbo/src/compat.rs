@@ -0,0 +1,300 @@
+//! Checks payloads against the Turbo client an application actually ships.
+//!
+//! Turbo silently ignores actions it doesn't know, so a payload rendered for a newer client than
+//! the deployed one fails without a trace. Declare the [`Target`] once and check what is sent:
+//!
+//! ```
+//! use hotwire_turbo::compat::{Error, Target, TurboVersion};
+//! use hotwire_turbo::{power, stream};
+//!
+//! let target = Target::TURBO_7;
+//!
+//! assert_eq!(Ok(()), target.check(&stream::append("messages", "<p>Hi</p>")));
+//! assert_eq!(
+//!     Err(Error::Unsupported {
+//!         action: "set_title".to_string(),
+//!         requires: Target::new(TurboVersion::Turbo7).with_power(1),
+//!     }),
+//!     target.check(&power::set_title("Inbox"))
+//! );
+//! assert_eq!(Ok(()), target.with_power(5).check(&power::set_title("Inbox")));
+//! ```
+use crate::parse::{self, Stream};
+use crate::registry;
+use std::fmt;
+
+/// A Turbo release line. Later variants support everything earlier ones do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TurboVersion {
+    /// Turbo 7.2 or later, the first release with custom stream actions.
+    Turbo7,
+    /// Turbo 8, adding `refresh` and morphing `replace` and `update`.
+    Turbo8,
+}
+
+impl fmt::Display for TurboVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TurboVersion::Turbo7 => f.write_str("Turbo 7"),
+            TurboVersion::Turbo8 => f.write_str("Turbo 8"),
+        }
+    }
+}
+
+/// A client: its Turbo version, the minor version when it ships `turbo_power` 0.x, and whether
+/// it ships TurboBoost Commands.
+///
+/// The axes are independent, so targets are only partially ordered: Turbo 8 alone doesn't run
+/// Turbo Power actions, Turbo 7 with Turbo Power doesn't morph, and neither runs `invoke`
+/// without TurboBoost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub turbo: TurboVersion,
+    pub power: Option<u32>,
+    pub boost: bool,
+}
+
+impl Target {
+    pub const TURBO_7: Target = Target::new(TurboVersion::Turbo7);
+    pub const TURBO_8: Target = Target::new(TurboVersion::Turbo8);
+
+    pub const fn new(turbo: TurboVersion) -> Self {
+        Target {
+            turbo,
+            power: None,
+            boost: false,
+        }
+    }
+
+    /// The client also ships `turbo_power` 0.`minor`.
+    pub const fn with_power(mut self, minor: u32) -> Self {
+        self.power = Some(minor);
+        self
+    }
+
+    /// The client also ships TurboBoost Commands, which registers the `invoke` action.
+    pub const fn with_boost(mut self) -> Self {
+        self.boost = true;
+        self
+    }
+
+    /// Whether this client ships everything `requires` needs, comparing each axis separately.
+    pub fn satisfies(&self, requires: &Target) -> bool {
+        self.turbo >= requires.turbo
+            && requires
+                .power
+                .is_none_or(|minor| self.power.is_some_and(|power| power >= minor))
+            && (self.boost || !requires.boost)
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.turbo.fmt(f)?;
+        if let Some(minor) = self.power {
+            write!(f, " with Turbo Power 0.{minor}")?;
+        }
+        match (self.boost, self.power) {
+            (true, Some(_)) => f.write_str(" and TurboBoost"),
+            (true, None) => f.write_str(" with TurboBoost"),
+            (false, _) => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(parse::Error),
+    /// The action, or the way it is used, needs a newer client.
+    Unsupported {
+        action: String,
+        requires: Target,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => error.fmt(f),
+            Error::Unsupported { action, requires } => {
+                write!(f, "`{action}` requires {requires}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl Target {
+    /// Checks every `<turbo-stream>` element of the payload, failing on the first one this
+    /// client can't run. Actions this crate doesn't know are assumed to be custom actions
+    /// registered by the application.
+    pub fn check(&self, payload: &str) -> Result<(), Error> {
+        for stream in parse::parse(payload)? {
+            if let Some(requires) = requirement(&stream) {
+                if !self.satisfies(&requires) {
+                    return Err(Error::Unsupported {
+                        action: stream.action().to_string(),
+                        requires,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this client runs the stream.
+    pub fn supports(&self, stream: &Stream) -> bool {
+        requirement(stream).is_none_or(|requires| self.satisfies(&requires))
+    }
+}
+
+/// The oldest client running the stream, `None` for actions missing from the [`registry`].
+///
+/// [`registry`]: crate::registry
+pub fn requirement(stream: &Stream) -> Option<Target> {
+    match stream.action() {
+        "replace" | "update" if stream.attribute("method") == Some("morph") => {
+            Some(Target::TURBO_8)
+        }
+        action => registry::find(action).map(|action| action.since),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boost::{self, Receiver};
+    use crate::{power, stream, TurboAction};
+    use pretty_assertions::assert_eq;
+
+    fn unsupported(action: &str, requires: Target) -> Result<(), Error> {
+        Err(Error::Unsupported {
+            action: action.to_string(),
+            requires,
+        })
+    }
+
+    fn power(minor: u32) -> Target {
+        Target::TURBO_7.with_power(minor)
+    }
+
+    #[test]
+    fn satisfies() {
+        assert!(Target::TURBO_8.satisfies(&Target::TURBO_7));
+        assert!(!Target::TURBO_7.satisfies(&Target::TURBO_8));
+        assert!(Target::TURBO_8.with_power(5).satisfies(&power(2)));
+        assert!(!power(2).satisfies(&power(5)));
+        // Neither axis implies the other.
+        assert!(!Target::TURBO_8.satisfies(&power(1)));
+        assert!(!power(6).satisfies(&Target::TURBO_8));
+        assert!(!Target::TURBO_8
+            .with_power(6)
+            .satisfies(&Target::TURBO_7.with_boost()));
+        assert!(Target::TURBO_8.with_boost().satisfies(&Target::TURBO_7));
+    }
+
+    #[test]
+    fn turbo_7() {
+        let target = Target::TURBO_7;
+        assert_eq!(Ok(()), target.check(&stream::remove_all(".flash")));
+        assert_eq!(
+            unsupported("refresh", Target::TURBO_8),
+            target.check(r#"<turbo-stream action="refresh"></turbo-stream>"#)
+        );
+        assert_eq!(
+            unsupported("replace", Target::TURBO_8),
+            target.check(
+                r#"<turbo-stream action="replace" method="morph" target="x"><template></template></turbo-stream>"#
+            )
+        );
+    }
+
+    #[test]
+    fn turbo_8() {
+        let target = Target::TURBO_8;
+        assert_eq!(
+            Ok(()),
+            target.check(r#"<turbo-stream action="refresh"></turbo-stream>"#)
+        );
+        assert_eq!(
+            unsupported("graft", power(1)),
+            target.check(&power::graft("#item", "#list"))
+        );
+    }
+
+    #[test]
+    fn turbo_power() {
+        let payload = power::add_css_class(".item", "active") + &power::reset_form("#form");
+        assert_eq!(
+            unsupported("reset_form", power(5)),
+            power(4).check(&payload)
+        );
+        assert_eq!(Ok(()), power(5).check(&payload));
+    }
+
+    #[test]
+    fn turbo_boost() {
+        let payload = boost::invoke(Receiver::Document, "startViewTransition").render();
+        assert_eq!(
+            unsupported("invoke", Target::TURBO_7.with_boost()),
+            Target::TURBO_8.with_power(6).check(&payload)
+        );
+        assert_eq!(Ok(()), Target::TURBO_8.with_boost().check(&payload));
+    }
+
+    #[test]
+    fn custom_actions() {
+        let payload = r#"<turbo-stream action="confetti"></turbo-stream>"#;
+        assert_eq!(Ok(()), Target::TURBO_7.check(payload));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(Error::Parse(parse::Error::UnexpectedContent(0))),
+            Target::TURBO_8.check("<p>Hi</p>")
+        );
+    }
+
+    #[test]
+    fn power_actions() {
+        let streams = parse::parse(
+            &[
+                power::history_go(1),
+                power::notification("Hi", &mut Default::default(), ""),
+                power::turbo_progress_bar_set_value("0.5"),
+                power::set_cookie("a=1"),
+            ]
+            .concat(),
+        )
+        .unwrap();
+        assert!(streams.iter().all(|stream| requirement(stream).is_some()));
+    }
+
+    #[test]
+    fn error_message() {
+        let error = Target::TURBO_7.check(&power::reload()).unwrap_err();
+        assert_eq!(
+            "`reload` requires Turbo 7 with Turbo Power 0.1",
+            error.to_string()
+        );
+        assert_eq!(
+            "Turbo 8 with Turbo Power 0.6 and TurboBoost",
+            Target::TURBO_8.with_power(6).with_boost().to_string()
+        );
+        assert_eq!(
+            "`invoke` requires Turbo 7 with TurboBoost",
+            Target::TURBO_7
+                .check(&boost::invoke(Receiver::Window, "print").render())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
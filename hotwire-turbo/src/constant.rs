@@ -0,0 +1,594 @@
+//! Turbo Stream tags rendered at compile time.
+//!
+//! The functions in this module mirror the [`stream`](crate::stream) and [`power`](crate::power)
+//! helpers that only take string arguments, but are `const` and return a [`Tag`] instead of a
+//! `String`. They are meant to be used through [`static_turbo_stream!`](crate::static_turbo_stream),
+//! which renders the tags into a `&'static str` while compiling:
+//!
+//! ```
+//! use hotwire_turbo::{power, static_turbo_stream, stream};
+//!
+//! const PAYLOAD: &str = static_turbo_stream! {
+//!     remove "msg_1";
+//!     reload;
+//! };
+//!
+//! assert_eq!(
+//!     format!("{}{}", stream::remove("msg_1"), power::reload()),
+//!     PAYLOAD
+//! );
+//! ```
+//!
+//! `power::history_go` and `power::notification` take non-string arguments and have no
+//! compile-time counterpart.
+
+const MAX_ATTRIBUTES: usize = 4;
+
+/// A `<turbo-stream>` tag whose attributes and content are known at compile time.
+#[derive(Clone, Copy, Debug)]
+pub struct Tag {
+    attributes: [(&'static str, &'static str); MAX_ATTRIBUTES],
+    len: usize,
+    content: &'static str,
+}
+
+impl Tag {
+    pub const fn new(action: &'static str) -> Self {
+        Tag {
+            attributes: [("", ""); MAX_ATTRIBUTES],
+            len: 0,
+            content: "",
+        }
+        .attribute("action", action)
+    }
+
+    /// Adds an attribute, keeping the attributes ordered by name like the runtime helpers do.
+    pub const fn attribute(mut self, name: &'static str, value: &'static str) -> Self {
+        assert!(self.len < MAX_ATTRIBUTES, "too many attributes");
+        let mut index = self.len;
+        while index > 0 && less(name, self.attributes[index - 1].0) {
+            self.attributes[index] = self.attributes[index - 1];
+            index -= 1;
+        }
+        self.attributes[index] = (name, value);
+        self.len += 1;
+        self
+    }
+
+    pub const fn content(mut self, content: &'static str) -> Self {
+        self.content = content;
+        self
+    }
+
+    const fn rendered_len(&self) -> usize {
+        let mut len = OPEN.len() + CLOSE.len() + self.content.len();
+        let mut index = 0;
+        while index < self.len {
+            let (name, value) = self.attributes[index];
+            len += 4 + name.len() + escaped_len(value);
+            index += 1;
+        }
+        len
+    }
+}
+
+const OPEN: &str = "<turbo-stream><template>";
+const CLOSE: &str = "</template></turbo-stream>";
+
+/// The length of `tags` once rendered.
+pub const fn len(tags: &[Tag]) -> usize {
+    let mut len = 0;
+    let mut index = 0;
+    while index < tags.len() {
+        len += tags[index].rendered_len();
+        index += 1;
+    }
+    len
+}
+
+/// Renders `tags` into a buffer of exactly [`len`] bytes.
+pub const fn render<const N: usize>(tags: &[Tag]) -> [u8; N] {
+    let mut output = [0; N];
+    let mut position = 0;
+    let mut index = 0;
+    while index < tags.len() {
+        let tag = &tags[index];
+        position = write(&mut output, position, "<turbo-stream", false);
+        let mut attribute = 0;
+        while attribute < tag.len {
+            let (name, value) = tag.attributes[attribute];
+            position = write(&mut output, position, " ", false);
+            position = write(&mut output, position, name, false);
+            position = write(&mut output, position, "=\"", false);
+            position = write(&mut output, position, value, true);
+            position = write(&mut output, position, "\"", false);
+            attribute += 1;
+        }
+        position = write(&mut output, position, "><template>", false);
+        position = write(&mut output, position, tag.content, false);
+        position = write(&mut output, position, CLOSE, false);
+        index += 1;
+    }
+    assert!(
+        position == N,
+        "buffer length doesn't match the rendered tags"
+    );
+    output
+}
+
+pub const fn as_str(bytes: &[u8]) -> &str {
+    match core::str::from_utf8(bytes) {
+        Ok(payload) => payload,
+        Err(_) => panic!("rendered tags are not valid UTF-8"),
+    }
+}
+
+const fn escape(byte: u8) -> Option<&'static str> {
+    match byte {
+        b'&' => Some("&amp;"),
+        b'<' => Some("&lt;"),
+        b'>' => Some("&gt;"),
+        b'"' => Some("&quot;"),
+        _ => None,
+    }
+}
+
+const fn escaped_len(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let mut len = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        len += match escape(bytes[index]) {
+            Some(entity) => entity.len(),
+            None => 1,
+        };
+        index += 1;
+    }
+    len
+}
+
+const fn write<const N: usize>(
+    output: &mut [u8; N],
+    mut position: usize,
+    value: &str,
+    escaped: bool,
+) -> usize {
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match escape(bytes[index]) {
+            Some(entity) if escaped => position = write(output, position, entity, false),
+            _ => {
+                output[position] = bytes[index];
+                position += 1;
+            }
+        }
+        index += 1;
+    }
+    position
+}
+
+const fn less(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut index = 0;
+    while index < a.len() && index < b.len() {
+        if a[index] != b[index] {
+            return a[index] < b[index];
+        }
+        index += 1;
+    }
+    a.len() < b.len()
+}
+
+const fn target(action: &'static str, target: &'static str) -> Tag {
+    Tag::new(action).attribute("target", target)
+}
+
+const fn targets(action: &'static str, targets: &'static str) -> Tag {
+    Tag::new(action).attribute("targets", targets)
+}
+
+pub const fn remove(target: &'static str) -> Tag {
+    self::target("remove", target)
+}
+
+pub const fn remove_all(targets: &'static str) -> Tag {
+    self::targets("remove", targets)
+}
+
+pub const fn replace(target: &'static str, content: &'static str) -> Tag {
+    self::target("replace", target).content(content)
+}
+
+pub const fn replace_all(targets: &'static str, content: &'static str) -> Tag {
+    self::targets("replace", targets).content(content)
+}
+
+pub const fn before(target: &'static str, content: &'static str) -> Tag {
+    self::target("before", target).content(content)
+}
+
+pub const fn before_all(targets: &'static str, content: &'static str) -> Tag {
+    self::targets("before", targets).content(content)
+}
+
+pub const fn after(target: &'static str, content: &'static str) -> Tag {
+    self::target("after", target).content(content)
+}
+
+pub const fn after_all(targets: &'static str, content: &'static str) -> Tag {
+    self::targets("after", targets).content(content)
+}
+
+pub const fn update(target: &'static str, content: &'static str) -> Tag {
+    self::target("update", target).content(content)
+}
+
+pub const fn update_all(targets: &'static str, content: &'static str) -> Tag {
+    self::targets("update", targets).content(content)
+}
+
+pub const fn append(target: &'static str, content: &'static str) -> Tag {
+    self::target("append", target).content(content)
+}
+
+pub const fn append_all(targets: &'static str, content: &'static str) -> Tag {
+    self::targets("append", targets).content(content)
+}
+
+pub const fn prepend(target: &'static str, content: &'static str) -> Tag {
+    self::target("prepend", target).content(content)
+}
+
+pub const fn prepend_all(targets: &'static str, content: &'static str) -> Tag {
+    self::targets("prepend", targets).content(content)
+}
+
+pub const fn add_css_class(targets: &'static str, classes: &'static str) -> Tag {
+    self::targets("add_css_class", targets).attribute("classes", classes)
+}
+
+pub const fn toggle_css_class(targets: &'static str, classes: &'static str) -> Tag {
+    self::targets("toggle_css_class", targets).attribute("classes", classes)
+}
+
+pub const fn replace_css_class(targets: &'static str, from: &'static str, to: &'static str) -> Tag {
+    self::targets("replace_css_class", targets)
+        .attribute("from", from)
+        .attribute("to", to)
+}
+
+pub const fn clear_local_storage() -> Tag {
+    clear_storage("local")
+}
+
+pub const fn clear_session_storage() -> Tag {
+    clear_storage("session")
+}
+
+pub const fn clear_storage(storage_type: &'static str) -> Tag {
+    Tag::new("clear_storage").attribute("type", storage_type)
+}
+
+pub const fn console_log(level: &'static str, message: &'static str) -> Tag {
+    Tag::new("console_log")
+        .attribute("level", level)
+        .attribute("message", message)
+}
+
+pub const fn console_table(data: &'static str, columns: &'static str) -> Tag {
+    Tag::new("console_table")
+        .attribute("data", data)
+        .attribute("columns", columns)
+}
+
+pub const fn dispatch_event(
+    targets: &'static str,
+    name: &'static str,
+    detail: &'static str,
+) -> Tag {
+    self::targets("dispatch_event", targets)
+        .attribute("name", name)
+        .content(detail)
+}
+
+pub const fn graft(targets: &'static str, parent: &'static str) -> Tag {
+    self::targets("graft", targets).attribute("parent", parent)
+}
+
+pub const fn history_back() -> Tag {
+    Tag::new("history_back")
+}
+
+pub const fn history_forward() -> Tag {
+    Tag::new("history_forward")
+}
+
+pub const fn inner_html(targets: &'static str, html: &'static str) -> Tag {
+    self::targets("inner_html", targets).content(html)
+}
+
+pub const fn insert_adjacent_html(
+    targets: &'static str,
+    position: &'static str,
+    html: &'static str,
+) -> Tag {
+    self::targets("insert_adjacent_html", targets)
+        .attribute("position", position)
+        .content(html)
+}
+
+pub const fn insert_adjacent_text(
+    targets: &'static str,
+    position: &'static str,
+    text: &'static str,
+) -> Tag {
+    self::targets("insert_adjacent_text", targets)
+        .attribute("position", position)
+        .attribute("text", text)
+}
+
+pub const fn morph(targets: &'static str, html: &'static str) -> Tag {
+    self::targets("morph", targets).content(html)
+}
+
+pub const fn outer_html(targets: &'static str, html: &'static str) -> Tag {
+    self::targets("outer_html", targets).content(html)
+}
+
+pub const fn push_state(url: &'static str, title: &'static str, state: &'static str) -> Tag {
+    Tag::new("push_state")
+        .attribute("url", url)
+        .attribute("title", title)
+        .attribute("state", state)
+}
+
+pub const fn redirect_to(
+    url: &'static str,
+    turbo_action: &'static str,
+    turbo_frame: Option<&'static str>,
+) -> Tag {
+    let tag = Tag::new("redirect_to")
+        .attribute("url", url)
+        .attribute("turbo-action", turbo_action);
+    match turbo_frame {
+        Some(turbo_frame) => tag.attribute("turbo-frame", turbo_frame),
+        None => tag,
+    }
+}
+
+pub const fn reload() -> Tag {
+    Tag::new("reload")
+}
+
+pub const fn remove_attribute(targets: &'static str, attribute: &'static str) -> Tag {
+    self::targets("remove_attribute", targets).attribute("attribute", attribute)
+}
+
+pub const fn remove_css_class(targets: &'static str, classes: &'static str) -> Tag {
+    self::targets("remove_css_class", targets).attribute("classes", classes)
+}
+
+pub const fn remove_local_storage_item(key: &'static str) -> Tag {
+    remove_storage_item(key, "local")
+}
+
+pub const fn remove_session_storage_item(key: &'static str) -> Tag {
+    remove_storage_item(key, "session")
+}
+
+pub const fn remove_storage_item(key: &'static str, storage_type: &'static str) -> Tag {
+    Tag::new("remove_storage_item")
+        .attribute("key", key)
+        .attribute("type", storage_type)
+}
+
+pub const fn replace_state(url: &'static str, title: &'static str, state: &'static str) -> Tag {
+    Tag::new("replace_state")
+        .attribute("url", url)
+        .attribute("title", title)
+        .attribute("state", state)
+}
+
+pub const fn reset_form(targets: &'static str) -> Tag {
+    self::targets("reset_form", targets)
+}
+
+pub const fn scroll_into_view(targets: &'static str) -> Tag {
+    self::targets("scroll_into_view", targets)
+}
+
+pub const fn set_attribute(
+    targets: &'static str,
+    attribute: &'static str,
+    value: &'static str,
+) -> Tag {
+    self::targets("set_attribute", targets)
+        .attribute("attribute", attribute)
+        .attribute("value", value)
+}
+
+pub const fn set_cookie(cookie: &'static str) -> Tag {
+    Tag::new("set_cookie").attribute("cookie", cookie)
+}
+
+pub const fn set_cookie_item(key: &'static str, value: &'static str) -> Tag {
+    Tag::new("set_cookie_item")
+        .attribute("key", key)
+        .attribute("value", value)
+}
+
+pub const fn set_dataset_attribute(
+    targets: &'static str,
+    attribute: &'static str,
+    value: &'static str,
+) -> Tag {
+    self::targets("set_dataset_attribute", targets)
+        .attribute("attribute", attribute)
+        .attribute("value", value)
+}
+
+pub const fn set_focus(targets: &'static str) -> Tag {
+    self::targets("set_focus", targets)
+}
+
+pub const fn set_storage_local_item(key: &'static str, value: &'static str) -> Tag {
+    set_storage_item(key, value, "local")
+}
+
+pub const fn set_meta(name: &'static str, content: &'static str) -> Tag {
+    Tag::new("set_meta")
+        .attribute("name", name)
+        .attribute("content", content)
+}
+
+pub const fn set_property(targets: &'static str, name: &'static str, value: &'static str) -> Tag {
+    self::targets("set_property", targets)
+        .attribute("name", name)
+        .attribute("value", value)
+}
+
+pub const fn set_storage_session_item(key: &'static str, value: &'static str) -> Tag {
+    set_storage_item(key, value, "session")
+}
+
+pub const fn set_storage_item(
+    key: &'static str,
+    value: &'static str,
+    storage_type: &'static str,
+) -> Tag {
+    Tag::new("set_storage_item")
+        .attribute("key", key)
+        .attribute("value", value)
+        .attribute("type", storage_type)
+}
+
+pub const fn set_style(targets: &'static str, name: &'static str, value: &'static str) -> Tag {
+    self::targets("set_style", targets)
+        .attribute("name", name)
+        .attribute("value", value)
+}
+
+pub const fn set_styles(targets: &'static str, styles: &'static str) -> Tag {
+    self::targets("set_styles", targets).attribute("styles", styles)
+}
+
+pub const fn set_title(title: &'static str) -> Tag {
+    Tag::new("set_title").attribute("title", title)
+}
+
+pub const fn set_value(targets: &'static str, value: &'static str) -> Tag {
+    self::targets("set_value", targets).attribute("value", value)
+}
+
+pub const fn text_content(targets: &'static str, text: &'static str) -> Tag {
+    self::targets("text_content", targets).attribute("text", text)
+}
+
+#[cfg(feature = "turbo-power-0-6")]
+pub const fn toggle_attribute(
+    targets: &'static str,
+    attribute: &'static str,
+    force: Option<bool>,
+) -> Tag {
+    let tag = self::targets("toggle_attribute", targets).attribute("attribute", attribute);
+    match force {
+        Some(true) => tag.attribute("force", "true"),
+        Some(false) => tag.attribute("force", "false"),
+        None => tag,
+    }
+}
+
+pub const fn turbo_clear_cache() -> Tag {
+    Tag::new("turbo_clear_cache")
+}
+
+pub const fn turbo_frame_reload(target: &'static str) -> Tag {
+    self::target("turbo_frame_reload", target)
+}
+
+pub const fn turbo_frame_set_src(target: &'static str, src: &'static str) -> Tag {
+    self::target("turbo_frame_set_src", target).attribute("src", src)
+}
+
+pub const fn turbo_progress_bar_hide() -> Tag {
+    Tag::new("turbo_progress_bar_hide")
+}
+
+pub const fn turbo_progress_bar_set_value(value: &'static str) -> Tag {
+    Tag::new("turbo_progress_bar_set_value").attribute("value", value)
+}
+
+pub const fn turbo_progress_bar_show() -> Tag {
+    Tag::new("turbo_progress_bar_show")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{power, static_turbo_stream, stream};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn without_attributes() {
+        const PAYLOAD: &str = static_turbo_stream! { turbo_clear_cache };
+        assert_eq!(power::turbo_clear_cache(), PAYLOAD);
+    }
+
+    #[test]
+    fn attributes_are_ordered() {
+        const PAYLOAD: &str = static_turbo_stream! {
+            set_storage_item "my-key", "my-value", "local";
+        };
+        assert_eq!(
+            power::set_storage_item("my-key", "my-value", "local"),
+            PAYLOAD
+        );
+    }
+
+    #[test]
+    fn attributes_are_escaped() {
+        const PAYLOAD: &str = static_turbo_stream! {
+            console_table r#"["apples","oranges"]"#, r#"["fruits"]"#;
+        };
+        assert_eq!(
+            power::console_table(r#"["apples","oranges"]"#, r#"["fruits"]"#),
+            PAYLOAD
+        );
+    }
+
+    #[test]
+    fn optional_attribute() {
+        const PAYLOAD: &str = static_turbo_stream! {
+            redirect_to "/users/1", "advance", Some("my_frame");
+            redirect_to "/users/1", "advance", None;
+        };
+        assert_eq!(
+            format!(
+                "{}{}",
+                power::redirect_to("/users/1", "advance", Some("my_frame")),
+                power::redirect_to("/users/1", "advance", None)
+            ),
+            PAYLOAD
+        );
+    }
+
+    #[test]
+    fn sequence() {
+        const PAYLOAD: &str = static_turbo_stream! {
+            remove "msg_1";
+            append "messages" => "<p>Hello & welcome</p>";
+            history_back;
+            turbo_progress_bar_show;
+        };
+        assert_eq!(
+            format!(
+                "{}{}{}{}",
+                stream::remove("msg_1"),
+                stream::append("messages", "<p>Hello & welcome</p>"),
+                power::history_back(),
+                power::turbo_progress_bar_show()
+            ),
+            PAYLOAD
+        );
+    }
+}
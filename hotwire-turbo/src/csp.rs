@@ -0,0 +1,162 @@
+//! Content Security Policy nonces for inline `<script>` and `<style>` elements.
+//!
+//! Turbo only runs scripts inserted by stream actions when they carry the page's nonce, which it
+//! reads from the `csp-nonce` meta tag. A [`Nonce`] is created once per response and used both
+//! for that tag and for the template content of the response:
+//!
+//! ```
+//! use hotwire_turbo::{csp::Nonce, stream};
+//!
+//! let nonce = Nonce::new("r4nd0m");
+//! let content = nonce.stamp("<p>Saved</p><script>flash()</script>");
+//!
+//! assert_eq!(r#"<p>Saved</p><script nonce="r4nd0m">flash()</script>"#, content);
+//! assert_eq!(r#"<meta name="csp-nonce" content="r4nd0m">"#, nonce.meta_tag());
+//! let payload = stream::append("messages", content);
+//! ```
+use crate::html::{self, Token};
+use crate::{escape_attribute, meta};
+
+const ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// A Content Security Policy nonce for a single response.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Nonce(String);
+
+impl Nonce {
+    pub fn new(value: impl Into<String>) -> Self {
+        Nonce(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The `csp-nonce` meta tag for the document `<head>`.
+    pub fn meta_tag(&self) -> String {
+        meta::csp_nonce(&self.0)
+    }
+
+    /// Adds a `nonce` attribute to every `<script>` and `<style>` start tag in `html` that
+    /// doesn't have one yet.
+    ///
+    /// Tag names inside comments, attribute values and the text of raw text elements such as
+    /// `<script>` aren't start tags and are left alone.
+    pub fn stamp(&self, html: &str) -> String {
+        let attribute = format!(r#" nonce="{}""#, escape_attribute(&self.0));
+        let mut output = String::with_capacity(html.len() + attribute.len());
+        let mut copied = 0;
+        for (start, token) in html::tokens(html) {
+            let Token::StartTag {
+                name, attributes, ..
+            } = token
+            else {
+                continue;
+            };
+            if ELEMENTS
+                .iter()
+                .any(|element| element.eq_ignore_ascii_case(name))
+                && !has_nonce(attributes)
+            {
+                let name_end = start + 1 + name.len();
+                output.push_str(&html[copied..name_end]);
+                output.push_str(&attribute);
+                copied = name_end;
+            }
+        }
+        output.push_str(&html[copied..]);
+        output
+    }
+}
+
+fn has_nonce(attributes: &str) -> bool {
+    let attributes = attributes.to_ascii_lowercase();
+    attributes.match_indices("nonce").any(|(index, _)| {
+        let before = attributes[..index].chars().next_back();
+        let after = attributes[index + 5..].trim_start().chars().next();
+        before.is_some_and(char::is_whitespace) && matches!(after, Some('=' | '>' | '/') | None)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Nonce;
+    use pretty_assertions::assert_eq;
+
+    fn nonce() -> Nonce {
+        Nonce::new("abc123")
+    }
+
+    #[test]
+    fn script_and_style() {
+        let expected = r#"<style nonce="abc123">p { color: red }</style><script nonce="abc123" type="module">run()</script>"#;
+        assert_eq!(
+            expected,
+            nonce().stamp(r#"<style>p { color: red }</style><script type="module">run()</script>"#)
+        );
+    }
+
+    #[test]
+    fn uppercase_tags() {
+        let expected = r#"<SCRIPT nonce="abc123">run()</SCRIPT>"#;
+        assert_eq!(expected, nonce().stamp("<SCRIPT>run()</SCRIPT>"));
+    }
+
+    #[test]
+    fn existing_nonce_is_kept() {
+        let html = r#"<script nonce="other">run()</script>"#;
+        assert_eq!(html, nonce().stamp(html));
+    }
+
+    #[test]
+    fn other_elements_are_untouched() {
+        let html = r#"<p data-nonce="1">1 < 2</p><scripts></scripts><styles/><stylesheet>"#;
+        assert_eq!(html, nonce().stamp(html));
+    }
+
+    #[test]
+    fn quoted_greater_than() {
+        let expected = r#"<script nonce="abc123" data-x="a>b">run()</script>"#;
+        assert_eq!(
+            expected,
+            nonce().stamp(r#"<script data-x="a>b">run()</script>"#)
+        );
+    }
+
+    #[test]
+    fn comments_are_untouched() {
+        let expected = r#"<!-- <script>old()</script> --><script nonce="abc123">run()</script>"#;
+        assert_eq!(
+            expected,
+            nonce().stamp("<!-- <script>old()</script> --><script>run()</script>")
+        );
+    }
+
+    #[test]
+    fn attribute_values_are_untouched() {
+        let html = r#"<p title="<script>" data-x='<style nonce'>Hi</p>"#;
+        assert_eq!(html, nonce().stamp(html));
+    }
+
+    #[test]
+    fn raw_text_is_untouched() {
+        let expected =
+            r#"<script nonce="abc123">w("<style>")</script><textarea><script></textarea>"#;
+        assert_eq!(
+            expected,
+            nonce().stamp(r#"<script>w("<style>")</script><textarea><script></textarea>"#)
+        );
+    }
+
+    #[test]
+    fn nonce_is_escaped() {
+        let expected = r#"<script nonce="&quot;&gt;"></script>"#;
+        assert_eq!(expected, Nonce::new(r#"">"#).stamp("<script></script>"));
+    }
+
+    #[test]
+    fn meta_tag() {
+        let expected = r#"<meta name="csp-nonce" content="abc123">"#;
+        assert_eq!(expected, nonce().meta_tag());
+    }
+}
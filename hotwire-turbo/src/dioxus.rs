@@ -0,0 +1,268 @@
+//! Render [Dioxus](https://dioxuslabs.com) components as Turbo Stream template content.
+//!
+//! Components are passed as closures so they are built, and rendered to HTML, inside their own
+//! virtual DOM:
+//!
+//! ```ignore
+//! hotwire_turbo::dioxus::append("messages", || rsx! { Message { text: "Hello" } })
+//! ```
+//!
+//! Event listeners are dropped, as are `false` and unset attributes. A `style` attribute and
+//! style properties are merged into one `style` attribute, and the text of `script` and `style`
+//! elements is written as is.
+use crate::html::VOID_ELEMENTS;
+use crate::{escape_attribute, power, stream};
+use ::dioxus::dioxus_core::{
+    AttributeValue, DynamicNode, Element, TemplateAttribute, TemplateNode, VNode, VirtualDom,
+};
+use std::fmt::Write;
+use std::rc::Rc;
+
+#[derive(Clone)]
+struct Root(Rc<dyn Fn() -> Element>);
+
+/// Renders the element returned by `component` to an HTML string.
+pub fn render<F>(component: F) -> String
+where
+    F: Fn() -> Element + 'static,
+{
+    let mut dom = VirtualDom::new_with_props(|Root(root): Root| root(), Root(Rc::new(component)));
+    dom.rebuild_in_place();
+    let mut html = String::new();
+    write_vnode(&mut html, dom.base_scope().root_node(), &dom, false);
+    html
+}
+
+pub fn replace<F>(target: &str, component: F) -> String
+where
+    F: Fn() -> Element + 'static,
+{
+    stream::replace(target, render(component))
+}
+
+pub fn append<F>(target: &str, component: F) -> String
+where
+    F: Fn() -> Element + 'static,
+{
+    stream::append(target, render(component))
+}
+
+pub fn morph<F>(targets: &str, component: F) -> String
+where
+    F: Fn() -> Element + 'static,
+{
+    power::morph(targets, render(component))
+}
+
+/// Elements whose text isn't escaped.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+fn write_vnode(html: &mut String, vnode: &VNode, dom: &VirtualDom, raw_text: bool) {
+    for root in vnode.template.roots {
+        write_template_node(html, root, vnode, dom, raw_text);
+    }
+}
+
+fn write_template_node(
+    html: &mut String,
+    node: &TemplateNode,
+    vnode: &VNode,
+    dom: &VirtualDom,
+    raw_text: bool,
+) {
+    match node {
+        TemplateNode::Element {
+            tag,
+            attrs,
+            children,
+            ..
+        } => {
+            let mut style = String::new();
+            let mut inner_html = None;
+            let _ = write!(html, "<{tag}");
+            for attribute in attrs.iter() {
+                match attribute {
+                    TemplateAttribute::Static {
+                        name,
+                        value,
+                        namespace,
+                    } => write_attribute(html, &mut style, name, value, *namespace),
+                    TemplateAttribute::Dynamic { id } => {
+                        for attribute in vnode.dynamic_attrs[*id].iter() {
+                            let value = match &attribute.value {
+                                AttributeValue::Text(value) => value.clone(),
+                                AttributeValue::Float(value) => value.to_string(),
+                                AttributeValue::Int(value) => value.to_string(),
+                                AttributeValue::Bool(true) => String::new(),
+                                _ => continue,
+                            };
+                            if attribute.name == "dangerous_inner_html" {
+                                inner_html = Some(value);
+                            } else {
+                                write_attribute(
+                                    html,
+                                    &mut style,
+                                    attribute.name,
+                                    &value,
+                                    attribute.namespace,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            if !style.is_empty() {
+                let _ = write!(html, r#" style="{}""#, escape_attribute(&style));
+            }
+            html.push('>');
+            if VOID_ELEMENTS.contains(tag) {
+                return;
+            }
+            match inner_html {
+                Some(inner_html) => html.push_str(&inner_html),
+                None => {
+                    let raw_text = RAW_TEXT_ELEMENTS.contains(tag);
+                    for child in children.iter() {
+                        write_template_node(html, child, vnode, dom, raw_text);
+                    }
+                }
+            }
+            let _ = write!(html, "</{tag}>");
+        }
+        TemplateNode::Text { text } => write_text(html, text, raw_text),
+        TemplateNode::Dynamic { id } => match &vnode.dynamic_nodes[*id] {
+            DynamicNode::Text(text) => write_text(html, &text.value, raw_text),
+            DynamicNode::Fragment(nodes) => {
+                for node in nodes {
+                    write_vnode(html, node, dom, raw_text);
+                }
+            }
+            DynamicNode::Component(component) => {
+                if let Some(scope) = component.mounted_scope(*id, vnode, dom) {
+                    write_vnode(html, scope.root_node(), dom, raw_text);
+                }
+            }
+            DynamicNode::Placeholder(_) => {}
+        },
+    }
+}
+
+fn write_text(html: &mut String, text: &str, raw_text: bool) {
+    if raw_text {
+        html.push_str(text);
+    } else {
+        html.push_str(&html_escape::encode_text(text));
+    }
+}
+
+fn write_attribute(
+    html: &mut String,
+    style: &mut String,
+    name: &str,
+    value: &str,
+    namespace: Option<&str>,
+) {
+    match namespace {
+        Some("style") => {
+            separate_declarations(style);
+            let _ = write!(style, "{name}:{value};");
+        }
+        None if name == "style" => {
+            separate_declarations(style);
+            style.push_str(value);
+        }
+        _ if value.is_empty() => {
+            let _ = write!(html, " {name}");
+        }
+        _ => {
+            let _ = write!(html, r#" {name}="{}""#, escape_attribute(value));
+        }
+    }
+}
+
+fn separate_declarations(style: &mut String) {
+    if !style.is_empty() && !style.trim_end().ends_with(';') {
+        style.push(';');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use ::dioxus::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[component]
+    fn Message(text: &'static str) -> Element {
+        rsx! { p { id: "message_1", "{text}" } }
+    }
+
+    #[test]
+    fn replace() {
+        let expected = r#"<turbo-stream action="replace" target="message_1"><template><p id="message_1">Hello</p></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            super::replace("message_1", || rsx! { Message { text: "Hello" } })
+        );
+    }
+
+    #[test]
+    fn append() {
+        let expected = r#"<turbo-stream action="append" target="messages"><template><p id="message_1">Hello</p></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            super::append("messages", || rsx! { Message { text: "Hello" } })
+        );
+    }
+
+    #[test]
+    fn morph() {
+        let expected = r##"<turbo-stream action="morph" targets="#message_1"><template><p id="message_1">Hello</p></template></turbo-stream>"##;
+        assert_eq!(
+            expected,
+            super::morph("#message_1", || rsx! { Message { text: "Hello" } })
+        );
+    }
+
+    #[test]
+    fn attributes_and_escaping() {
+        let count = 2;
+        let html = render(move || {
+            rsx! {
+                form { class: "new", "data-count": count, hidden: false,
+                    input { r#type: "text", disabled: true, value: "\"<b>\"" }
+                    ul { style: "margin: 0",
+                        for item in ["a&b", "c"] {
+                            li { color: "red", onclick: |_| {}, "{item}" }
+                        }
+                    }
+                }
+            }
+        });
+        assert_eq!(
+            r#"<form class="new" data-count="2"><input type="text" disabled value="&quot;&lt;b&gt;&quot;"><ul style="margin: 0"><li style="color:red;">a&amp;b</li><li style="color:red;">c</li></ul></form>"#,
+            html
+        );
+    }
+
+    #[test]
+    fn style_attribute_and_properties_merge() {
+        let html = render(|| rsx! { p { style: "margin: 0", color: "red", "Hi" } });
+        assert_eq!(r#"<p style="margin: 0;color:red;">Hi</p>"#, html);
+    }
+
+    #[test]
+    fn raw_text_elements() {
+        let html = render(|| {
+            rsx! {
+                script { "if (a < b && c) {{ go(\"x\") }}" }
+                style { "p > a {{ color: red }}" }
+                p { "a < b" }
+            }
+        });
+        assert_eq!(
+            r#"<script>if (a < b && c) { go("x") }</script><style>p > a { color: red }</style><p>a &lt; b</p>"#,
+            html
+        );
+    }
+}
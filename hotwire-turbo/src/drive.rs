@@ -0,0 +1,222 @@
+//! `data-turbo-*` attributes that configure Turbo Drive on links, forms and other elements.
+//!
+//! ```
+//! use hotwire_turbo::drive::{Attributes, Method};
+//!
+//! let attributes = Attributes::new()
+//!     .method(Method::Delete)
+//!     .confirm("Are you sure?");
+//!
+//! assert_eq!(
+//!     r#"data-turbo-confirm="Are you sure?" data-turbo-method="delete""#,
+//!     attributes.to_string()
+//! );
+//! ```
+use crate::escape_attribute;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// `data-turbo-method`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "get",
+            Method::Post => "post",
+            Method::Put => "put",
+            Method::Patch => "patch",
+            Method::Delete => "delete",
+        }
+    }
+}
+
+/// `data-turbo-action`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Advance,
+    Replace,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Advance => "advance",
+            Action::Replace => "replace",
+        }
+    }
+}
+
+/// `data-turbo-track`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Track {
+    /// Reload the page when the tracked asset changes.
+    Reload,
+    /// Remove the tracked element when it is missing from the next page.
+    Dynamic,
+}
+
+impl Track {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Track::Reload => "reload",
+            Track::Dynamic => "dynamic",
+        }
+    }
+}
+
+/// A set of `data-turbo-*` attributes, rendered in name order by its `Display` implementation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attributes {
+    attributes: BTreeMap<&'static str, String>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `data-turbo`, enables or disables Turbo Drive for the element and its descendants.
+    pub fn turbo(self, enabled: bool) -> Self {
+        self.set("data-turbo", enabled.to_string())
+    }
+
+    /// `data-turbo-method`, the HTTP method a link is followed with.
+    pub fn method(self, method: Method) -> Self {
+        self.set("data-turbo-method", method.as_str())
+    }
+
+    /// `data-turbo-confirm`, the message shown in a confirmation dialog before proceeding.
+    pub fn confirm(self, message: &str) -> Self {
+        self.set("data-turbo-confirm", message)
+    }
+
+    /// `data-turbo-frame`, the id of the frame to navigate, or `_top` for the whole page.
+    pub fn frame(self, frame: &str) -> Self {
+        self.set("data-turbo-frame", frame)
+    }
+
+    /// `data-turbo-action`, how the visit changes the browser history.
+    pub fn action(self, action: Action) -> Self {
+        self.set("data-turbo-action", action.as_str())
+    }
+
+    /// `data-turbo-stream`, accepts Turbo Stream responses for GET requests.
+    pub fn stream(self) -> Self {
+        self.set("data-turbo-stream", "true")
+    }
+
+    /// `data-turbo-preload`, loads the link into the cache as soon as it is rendered.
+    pub fn preload(self) -> Self {
+        self.set("data-turbo-preload", "true")
+    }
+
+    /// `data-turbo-prefetch`, enables or disables prefetching links on hover.
+    pub fn prefetch(self, enabled: bool) -> Self {
+        self.set("data-turbo-prefetch", enabled.to_string())
+    }
+
+    /// `data-turbo-permanent`, keeps the element, which needs an id, across page loads.
+    pub fn permanent(self) -> Self {
+        self.set("data-turbo-permanent", "true")
+    }
+
+    /// `data-turbo-track`, how changes to an asset in the `<head>` are handled.
+    pub fn track(self, track: Track) -> Self {
+        self.set("data-turbo-track", track.as_str())
+    }
+
+    /// `data-turbo-submits-with`, the text shown on the submitter while the form submits.
+    pub fn submits_with(self, text: &str) -> Self {
+        self.set("data-turbo-submits-with", text)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    fn set(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.attributes.insert(name, value.into());
+        self
+    }
+}
+
+impl fmt::Display for Attributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.attributes.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, r#"{name}="{}""#, escape_attribute(value))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn empty() {
+        assert!(Attributes::new().is_empty());
+        assert_eq!("", Attributes::new().to_string());
+    }
+
+    #[test]
+    fn turbo() {
+        let expected = r#"data-turbo="false""#;
+        assert_eq!(expected, Attributes::new().turbo(false).to_string());
+    }
+
+    #[test]
+    fn link() {
+        let expected = r#"data-turbo-action="replace" data-turbo-frame="_top" data-turbo-method="patch" data-turbo-stream="true""#;
+        let attributes = Attributes::new()
+            .method(Method::Patch)
+            .frame("_top")
+            .action(Action::Replace)
+            .stream();
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn preload_and_prefetch() {
+        let expected = r#"data-turbo-prefetch="false" data-turbo-preload="true""#;
+        let attributes = Attributes::new().preload().prefetch(false);
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn permanent_and_track() {
+        let expected = r#"data-turbo-permanent="true" data-turbo-track="reload""#;
+        let attributes = Attributes::new().track(Track::Reload).permanent();
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn values_are_escaped() {
+        let expected = r#"data-turbo-confirm="Delete &quot;Inbox&quot;?" data-turbo-submits-with="Saving &amp; closing""#;
+        let attributes = Attributes::new()
+            .confirm(r#"Delete "Inbox"?"#)
+            .submits_with("Saving & closing");
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn last_value_wins() {
+        let expected = r#"data-turbo-method="delete""#;
+        let attributes = Attributes::new()
+            .method(Method::Post)
+            .method(Method::Delete);
+        assert_eq!(expected, attributes.to_string());
+    }
+}
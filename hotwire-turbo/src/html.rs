@@ -0,0 +1,270 @@
+//! A minimal HTML tokenizer, for finding the tags of template content.
+//!
+//! It doesn't build a tree nor decode anything, but follows the tokenizer states that matter for
+//! telling tags apart: comments, quoted attribute values and the text of raw text elements don't
+//! produce tags.
+
+/// Elements without content nor end tag.
+pub(crate) const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is text up to their end tag.
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    /// A comment, or a doctype or other `<!…>` markup.
+    Comment(&'a str),
+    /// `attributes` runs from the end of the name up to the closing `>`, trailing `/` included.
+    StartTag {
+        name: &'a str,
+        attributes: &'a str,
+        self_closing: bool,
+    },
+    EndTag {
+        name: &'a str,
+    },
+}
+
+/// The tokens of `html`, each with its byte offset.
+pub(crate) fn tokens(html: &str) -> Tokens<'_> {
+    Tokens {
+        html,
+        position: 0,
+        raw_text: None,
+    }
+}
+
+pub(crate) fn is_void(name: &str) -> bool {
+    VOID_ELEMENTS
+        .iter()
+        .any(|void| void.eq_ignore_ascii_case(name))
+}
+
+pub(crate) struct Tokens<'a> {
+    html: &'a str,
+    position: usize,
+    /// The raw text element whose content comes next.
+    raw_text: Option<&'a str>,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (usize, Token<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+        let rest = &self.html[start..];
+        if rest.is_empty() {
+            return None;
+        }
+        if let Some(name) = self.raw_text.take() {
+            let len = raw_text_len(rest, name);
+            if len > 0 {
+                self.position += len;
+                return Some((start, Token::Text(&rest[..len])));
+            }
+        }
+        let starts_name = |tag: &&str| tag.starts_with(|c: char| c.is_ascii_alphabetic());
+        let (len, token) = if let Some(comment) = rest.strip_prefix("<!--") {
+            let len = comment.find("-->").map_or(rest.len(), |end| end + 7);
+            (len, Token::Comment(&rest[..len]))
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            let len = rest.find('>').map_or(rest.len(), |end| end + 1);
+            (len, Token::Comment(&rest[..len]))
+        } else if let Some(tag) = rest.strip_prefix("</").filter(starts_name) {
+            let name = &tag[..name_len(tag)];
+            let len = tag_end(tag).map_or(tag.len(), |end| end + 1);
+            (2 + len, Token::EndTag { name })
+        } else if let Some(tag) = rest.strip_prefix('<').filter(starts_name) {
+            let name = &tag[..name_len(tag)];
+            let end = tag_end(tag);
+            let attributes = &tag[name.len()..end.unwrap_or(tag.len())];
+            let self_closing = attributes.ends_with('/');
+            if !self_closing
+                && RAW_TEXT_ELEMENTS
+                    .iter()
+                    .any(|raw| raw.eq_ignore_ascii_case(name))
+            {
+                self.raw_text = Some(name);
+            }
+            let token = Token::StartTag {
+                name,
+                attributes,
+                self_closing,
+            };
+            (1 + end.map_or(tag.len(), |end| end + 1), token)
+        } else {
+            let len = rest[1..].find('<').map_or(rest.len(), |end| end + 1);
+            (len, Token::Text(&rest[..len]))
+        };
+        self.position += len;
+        Some((start, token))
+    }
+}
+
+fn name_len(tag: &str) -> usize {
+    tag.find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+        .unwrap_or(tag.len())
+}
+
+/// The position of the `>` closing the tag, the first one outside a quoted attribute value.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut after_equals = false;
+    for (index, c) in tag.char_indices() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '>') => return Some(index),
+            (None, '"' | '\'') if after_equals => quote = Some(c),
+            (None, '=') => {
+                after_equals = true;
+                continue;
+            }
+            (None, c) if after_equals && c.is_ascii_whitespace() => continue,
+            _ => {}
+        }
+        after_equals = false;
+    }
+    None
+}
+
+/// The length of the text before the end tag of the raw text element `name`.
+fn raw_text_len(text: &str, name: &str) -> usize {
+    let mut from = 0;
+    while let Some(index) = text[from..].find("</") {
+        let start = from + index;
+        let tag = &text.as_bytes()[start + 2..];
+        if tag.len() >= name.len()
+            && tag[..name.len()].eq_ignore_ascii_case(name.as_bytes())
+            && matches!(
+                tag.get(name.len()),
+                None | Some(b'>' | b'/' | b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')
+            )
+        {
+            return start;
+        }
+        from = start + 2;
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tags(html: &str) -> Vec<(usize, Token<'_>)> {
+        tokens(html)
+            .filter(|(_, token)| !matches!(token, Token::Text(_) | Token::Comment(_)))
+            .collect()
+    }
+
+    #[test]
+    fn elements() {
+        assert_eq!(
+            vec![
+                (0, Token::Text("a ")),
+                (
+                    2,
+                    Token::StartTag {
+                        name: "b",
+                        attributes: r#" class="x""#,
+                        self_closing: false
+                    }
+                ),
+                (15, Token::Text("c")),
+                (16, Token::EndTag { name: "b" }),
+                (
+                    20,
+                    Token::StartTag {
+                        name: "br",
+                        attributes: "/",
+                        self_closing: true
+                    }
+                ),
+            ],
+            tokens(r#"a <b class="x">c</b><br/>"#).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn comments_hold_no_tags() {
+        assert_eq!(
+            vec![
+                (0, Token::Comment("<!DOCTYPE html>")),
+                (15, Token::Comment("<!-- <script> -->")),
+                (32, Token::Text("<3 ")),
+            ],
+            tokens("<!DOCTYPE html><!-- <script> --><3 ").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn quoted_values_hold_no_tags() {
+        let html = r#"<a title='<b>' data-x="x > y" b"c>d</a>"#;
+        assert_eq!(
+            vec![
+                (
+                    0,
+                    Token::StartTag {
+                        name: "a",
+                        attributes: r#" title='<b>' data-x="x > y" b"c"#,
+                        self_closing: false
+                    }
+                ),
+                (35, Token::EndTag { name: "a" }),
+            ],
+            tags(html)
+        );
+    }
+
+    #[test]
+    fn raw_text_holds_no_tags() {
+        let html = "<SCRIPT>if (a <b) '</scripts>'</Script ><p>";
+        assert_eq!(
+            vec![
+                (
+                    0,
+                    Token::StartTag {
+                        name: "SCRIPT",
+                        attributes: "",
+                        self_closing: false
+                    }
+                ),
+                (30, Token::EndTag { name: "Script" }),
+                (
+                    40,
+                    Token::StartTag {
+                        name: "p",
+                        attributes: "",
+                        self_closing: false
+                    }
+                ),
+            ],
+            tags(html)
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert_eq!(
+            vec![(0, Token::Comment("<!-- a")),],
+            tokens("<!-- a").collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(
+                0,
+                Token::StartTag {
+                    name: "a",
+                    attributes: r#" href="x>"#,
+                    self_closing: false
+                }
+            )],
+            tokens(r#"<a href="x>"#).collect::<Vec<_>>()
+        );
+    }
+}
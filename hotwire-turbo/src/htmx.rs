@@ -0,0 +1,286 @@
+//! Renders stream payloads for [htmx](https://htmx.org) clients, as out of band swaps and
+//! `HX-*` response headers.
+//!
+//! ```
+//! use hotwire_turbo::{htmx, power, stream};
+//!
+//! let payload = stream::append("messages", "<p>Hi</p>") + &power::push_state("/inbox", "", "");
+//! let response = htmx::render(&payload)?;
+//!
+//! assert_eq!(
+//!     r##"<div hx-swap-oob="beforeend:#messages"><p>Hi</p></div>"##,
+//!     response.body
+//! );
+//! assert_eq!(vec![("HX-Push-Url", "/inbox".to_string())], response.headers);
+//! # Ok::<(), hotwire_turbo::htmx::Error>(())
+//! ```
+//!
+//! Swaps other than `replace` wrap the content in a `<div>` that htmx strips, so content the
+//! HTML parser won't nest in a `<div>`, such as table rows, doesn't survive the round trip.
+//! `replace` marks the new element itself, unless the content isn't a single element: it is then
+//! wrapped in a `<div>` that replaces the target and stays in the page.
+use crate::cable_ready::css_escape;
+use crate::escape_attribute;
+use crate::html::{self, Token};
+use crate::parse::{self, Stream};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(parse::Error),
+    /// The action, or the way it is used, has no htmx equivalent.
+    Unsupported(String),
+    /// A stream without the attribute the htmx equivalent needs.
+    MissingAttribute {
+        action: String,
+        attribute: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => error.fmt(f),
+            Error::Unsupported(action) => write!(f, "`{action}` has no htmx equivalent"),
+            Error::MissingAttribute { action, attribute } => {
+                write!(f, "`{action}` requires the `{attribute}` attribute")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Self {
+        Error::Parse(error)
+    }
+}
+
+/// An htmx response: out of band swaps in the body, plus headers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Response {
+    pub body: String,
+    pub headers: Vec<(&'static str, String)>,
+}
+
+/// Renders every `<turbo-stream>` element of the payload for htmx.
+pub fn render(payload: &str) -> Result<Response, Error> {
+    let mut response = Response::default();
+    for stream in parse::parse(payload)? {
+        match header(&stream)? {
+            Some(header) => response.headers.push(header),
+            None => response.body.push_str(&swap(&stream)?),
+        }
+    }
+    Ok(response)
+}
+
+/// The `HX-*` header for actions changing the page or history.
+fn header(stream: &Stream) -> Result<Option<(&'static str, String)>, Error> {
+    let name = match stream.action() {
+        // htmx can't redirect a single frame.
+        "redirect_to" if stream.attribute("turbo-frame").is_some() => {
+            return Err(Error::Unsupported("redirect_to".to_string()))
+        }
+        "redirect_to" => "HX-Redirect",
+        "push_state" => "HX-Push-Url",
+        "replace_state" => "HX-Replace-Url",
+        "reload" => return Ok(Some(("HX-Refresh", "true".to_string()))),
+        _ => return Ok(None),
+    };
+    Ok(Some((name, required(stream, "url")?.to_string())))
+}
+
+/// The out of band swap for a Turbo stream action.
+fn swap(stream: &Stream) -> Result<String, Error> {
+    let action = stream.action();
+    let strategy = match action {
+        "append" => "beforeend",
+        "prepend" => "afterbegin",
+        "before" => "beforebegin",
+        "after" => "afterend",
+        "update" => "innerHTML",
+        "replace" => "outerHTML",
+        "remove" => "delete",
+        _ => return Err(Error::Unsupported(action.to_string())),
+    };
+    let selector = match (stream.attribute("target"), stream.attribute("targets")) {
+        (Some(target), _) => format!("#{}", css_escape(target)),
+        (None, Some(targets)) => targets.to_string(),
+        (None, None) => {
+            return Err(Error::MissingAttribute {
+                action: action.to_string(),
+                attribute: "target",
+            })
+        }
+    };
+    let oob = format!(
+        r#" hx-swap-oob="{}""#,
+        escape_attribute(&format!("{strategy}:{selector}"))
+    );
+    let content = stream.content();
+    // outerHTML keeps the element carrying hx-swap-oob, so it goes on the new element itself.
+    if strategy == "outerHTML" {
+        if let Some(position) = single_element_name_end(content) {
+            let (start, rest) = content.split_at(position);
+            return Ok(format!("{start}{oob}{rest}"));
+        }
+    }
+    Ok(format!("<div{oob}>{content}</div>"))
+}
+
+fn required<'a>(stream: &'a Stream, attribute: &'static str) -> Result<&'a str, Error> {
+    stream
+        .attribute(attribute)
+        .ok_or_else(|| Error::MissingAttribute {
+            action: stream.action().to_string(),
+            attribute,
+        })
+}
+
+/// The end of the tag name of the only top-level element in `html`, `None` when there are
+/// several or text beside it.
+fn single_element_name_end(html: &str) -> Option<usize> {
+    let mut name_end = None;
+    let mut depth = 0usize;
+    for (start, token) in html::tokens(html) {
+        match token {
+            Token::Text(text) if depth == 0 && !text.trim().is_empty() => return None,
+            Token::Text(_) | Token::Comment(_) => {}
+            Token::StartTag {
+                name, self_closing, ..
+            } => {
+                if depth == 0 {
+                    if name_end.is_some() {
+                        return None;
+                    }
+                    name_end = Some(start + 1 + name.len());
+                }
+                if !self_closing && !html::is_void(name) {
+                    depth += 1;
+                }
+            }
+            Token::EndTag { .. } => depth = depth.saturating_sub(1),
+        }
+    }
+    name_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{power, stream};
+    use pretty_assertions::assert_eq;
+
+    fn body(payload: &str) -> String {
+        render(payload).unwrap().body
+    }
+
+    #[test]
+    fn swaps() {
+        assert_eq!(
+            r##"<div hx-swap-oob="afterbegin:#list"><li>1</li></div>"##,
+            body(&stream::prepend("list", "<li>1</li>"))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="beforebegin:#item_2"><li>1</li></div>"##,
+            body(&stream::before("item_2", "<li>1</li>"))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="afterend:#item_1"><li>2</li></div>"##,
+            body(&stream::after("item_1", "<li>2</li>"))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="innerHTML:#count">42</div>"##,
+            body(&stream::update("count", "42"))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="delete:#flash"></div>"##,
+            body(&stream::remove("flash"))
+        );
+    }
+
+    #[test]
+    fn replace_stamps_new_element() {
+        assert_eq!(
+            r##"<li hx-swap-oob="outerHTML:#item_1" id="item_1">One</li>"##,
+            body(&stream::replace("item_1", r#"<li id="item_1">One</li>"#))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="outerHTML:#item_1">One</div>"##,
+            body(&stream::replace("item_1", "One"))
+        );
+        assert_eq!(
+            r##" <tr hx-swap-oob="outerHTML:#row_1" id="row_1"><td>1</td></tr> "##,
+            body(&stream::replace(
+                "row_1",
+                r#" <tr id="row_1"><td>1</td></tr> "#
+            ))
+        );
+    }
+
+    #[test]
+    fn replace_wraps_several_elements() {
+        assert_eq!(
+            r##"<div hx-swap-oob="outerHTML:#item_1"><li id="item_1">One</li><li>Two</li></div>"##,
+            body(&stream::replace(
+                "item_1",
+                r#"<li id="item_1">One</li><li>Two</li>"#
+            ))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="outerHTML:#item_1"><img id="item_1"> One</div>"##,
+            body(&stream::replace("item_1", r#"<img id="item_1"> One"#))
+        );
+    }
+
+    #[test]
+    fn target_is_escaped() {
+        assert_eq!(
+            r##"<div hx-swap-oob="beforeend:#item\:1"><li>1</li></div>"##,
+            body(&stream::append("item:1", "<li>1</li>"))
+        );
+        assert_eq!(
+            r##"<div hx-swap-oob="innerHTML:#\31 st">One</div>"##,
+            body(&stream::update("1st", "One"))
+        );
+    }
+
+    #[test]
+    fn targets_selector() {
+        assert_eq!(
+            r#"<div hx-swap-oob="beforeend:.list &gt; ul"><li>1</li></div>"#,
+            body(&stream::append_all(".list > ul", "<li>1</li>"))
+        );
+    }
+
+    #[test]
+    fn headers() {
+        let payload = power::redirect_to("/login", "advance", None)
+            + &power::replace_state("/inbox?page=2", "", "")
+            + &power::reload();
+        let response = render(&payload).unwrap();
+        assert_eq!("", response.body);
+        assert_eq!(
+            vec![
+                ("HX-Redirect", "/login".to_string()),
+                ("HX-Replace-Url", "/inbox?page=2".to_string()),
+                ("HX-Refresh", "true".to_string()),
+            ],
+            response.headers
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(
+            Err(Error::Unsupported("set_focus".to_string())),
+            render(&power::set_focus("#input"))
+        );
+        assert_eq!(
+            Err(Error::Unsupported("redirect_to".to_string())),
+            render(&power::redirect_to("/inbox", "advance", Some("main")))
+        );
+    }
+}
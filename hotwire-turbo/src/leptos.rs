@@ -0,0 +1,81 @@
+//! Render [Leptos](https://leptos.dev) components as Turbo Stream template content.
+//!
+//! Components are passed as closures so they are created, and rendered with the Leptos SSR
+//! renderer, inside their own reactive owner:
+//!
+//! ```ignore
+//! hotwire_turbo::leptos::append("messages", || view! { <Message message/> })
+//! ```
+use crate::{power, stream};
+use ::leptos::prelude::{IntoView, Owner};
+
+/// Renders the view returned by `component` to an HTML string.
+pub fn render<F, V>(component: F) -> String
+where
+    F: FnOnce() -> V,
+    V: IntoView,
+{
+    Owner::new().with(|| component().to_html())
+}
+
+pub fn replace<F, V>(target: &str, component: F) -> String
+where
+    F: FnOnce() -> V,
+    V: IntoView,
+{
+    stream::replace(target, render(component))
+}
+
+pub fn append<F, V>(target: &str, component: F) -> String
+where
+    F: FnOnce() -> V,
+    V: IntoView,
+{
+    stream::append(target, render(component))
+}
+
+pub fn morph<F, V>(targets: &str, component: F) -> String
+where
+    F: FnOnce() -> V,
+    V: IntoView,
+{
+    power::morph(targets, render(component))
+}
+
+#[cfg(test)]
+mod tests {
+    use ::leptos::prelude::*;
+    use pretty_assertions::assert_eq;
+
+    #[component]
+    fn Message(text: &'static str) -> impl IntoView {
+        view! { <p id="message_1">{text}</p> }
+    }
+
+    #[test]
+    fn replace() {
+        let expected = r#"<turbo-stream action="replace" target="message_1"><template><p id="message_1">Hello</p></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            super::replace("message_1", || view! { <Message text="Hello"/> })
+        );
+    }
+
+    #[test]
+    fn append() {
+        let expected = r#"<turbo-stream action="append" target="messages"><template><p id="message_1">Hello</p></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            super::append("messages", || view! { <Message text="Hello"/> })
+        );
+    }
+
+    #[test]
+    fn morph() {
+        let expected = r##"<turbo-stream action="morph" targets="#message_1"><template><p id="message_1">Hello</p></template></turbo-stream>"##;
+        assert_eq!(
+            expected,
+            super::morph("#message_1", || view! { <Message text="Hello"/> })
+        );
+    }
+}
@@ -1,7 +1,116 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::fmt::Display;
+#[cfg(feature = "assets")]
+pub mod assets;
+pub mod boost;
+pub mod bridge;
+pub mod cable_ready;
+pub mod command;
+pub mod compat;
+pub mod constant;
+pub mod csp;
+#[cfg(feature = "dioxus")]
+pub mod dioxus;
+pub mod drive;
+mod html;
+pub mod htmx;
+#[cfg(feature = "leptos")]
+pub mod leptos;
+mod macros;
+#[cfg(feature = "mercure")]
+pub mod mercure;
+pub mod meta;
+pub mod parse;
+pub mod partial;
+pub mod path_configuration;
 pub mod power;
+pub mod registry;
+pub mod stimulus;
 pub mod stream;
+#[cfg(feature = "tera")]
+pub mod tera;
+#[cfg(feature = "derive")]
+pub use hotwire_turbo_macros::TurboAction;
+
+// Lets the code generated by the derive macros refer to `::hotwire_turbo` from inside this crate.
+extern crate self as hotwire_turbo;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use std::collections::BTreeMap;
+
+    /// The helpers `turbo_stream!` statements may name.
+    pub mod actions {
+        #[cfg(feature = "turbo-power-0-6")]
+        pub use crate::power::toggle_attribute;
+        pub use crate::power::{
+            add_css_class, clear_local_storage, clear_session_storage, clear_storage, console_log,
+            console_table, dispatch_event, graft, history_back, history_forward, history_go,
+            inner_html, insert_adjacent_html, insert_adjacent_text, morph, notification,
+            outer_html, push_state, redirect_to, reload, remove_attribute, remove_css_class,
+            remove_local_storage_item, remove_session_storage_item, remove_storage_item,
+            replace_css_class, replace_state, reset_form, scroll_into_view, set_attribute,
+            set_cookie, set_cookie_item, set_dataset_attribute, set_focus, set_meta, set_property,
+            set_storage_item, set_storage_local_item, set_storage_session_item, set_style,
+            set_styles, set_title, set_value, text_content, toggle_css_class, turbo_clear_cache,
+            turbo_frame_reload, turbo_frame_set_src, turbo_progress_bar_hide,
+            turbo_progress_bar_set_value, turbo_progress_bar_show,
+        };
+        pub use crate::stream::{
+            after, after_all, after_all_partial, after_partial, append, append_all,
+            append_all_partial, append_partial, before, before_all, before_all_partial,
+            before_partial, prepend, prepend_all, prepend_all_partial, prepend_partial, remove,
+            remove_all, remove_partial, replace, replace_all, replace_all_partial, replace_partial,
+            update, update_all, update_all_partial, update_partial,
+        };
+    }
+
+    pub fn action<A: crate::TurboAction + ?Sized>(action: &A) -> String {
+        action.render()
+    }
+
+    pub fn turbo_stream_tag<S: AsRef<str>>(
+        attributes: &mut BTreeMap<&str, &str>,
+        content: S,
+    ) -> String {
+        crate::turbo_stream_tag(attributes, content)
+    }
+}
+
+/// A user defined Turbo Stream action, registered on the client through `StreamActions`.
+///
+/// Usually derived with `#[derive(TurboAction)]`, which requires the `derive` feature.
+pub trait TurboAction {
+    /// Renders the `<turbo-stream>` tag for this action.
+    fn render(&self) -> String;
+}
+
 const STATIC_TAGS: &str = "<turbo-stream><template></template></turbo-stream>";
+
+/// Returns the DOM id for a record of the given `model`, following the conventions of
+/// `ActionView::RecordIdentifier.dom_id`. Records without an id are treated as new.
+///
+/// ```
+/// use hotwire_turbo::dom_id;
+///
+/// assert_eq!("clearance_5", dom_id("clearance", Some(5), None));
+/// assert_eq!("edit_clearance_5", dom_id("clearance", Some(5), Some("edit")));
+/// assert_eq!("new_clearance", dom_id("clearance", None::<u32>, None));
+/// ```
+pub fn dom_id<D: Display>(model: &str, id: Option<D>, prefix: Option<&str>) -> String {
+    match (id, prefix) {
+        (Some(id), Some(prefix)) => format!("{prefix}_{model}_{id}"),
+        (Some(id), None) => format!("{model}_{id}"),
+        (None, prefix) => format!("{}_{model}", prefix.unwrap_or("new")),
+    }
+}
+
+#[inline]
+pub(crate) fn escape_attribute(value: &str) -> Cow<'_, str> {
+    html_escape::encode_double_quoted_attribute(value)
+}
+
 #[inline]
 pub(crate) fn turbo_stream_action<'a, S: AsRef<str>>(
     action: &'a str,
@@ -43,7 +152,7 @@ pub(crate) fn turbo_stream_tag<S: AsRef<str>>(
     let attributes: BTreeMap<&str, String> = attributes
         .iter_mut()
         .map(|(key, value)| {
-            let value = html_escape::encode_double_quoted_attribute(*value);
+            let value = escape_attribute(value);
             (*key, value.to_string())
         })
         .collect();
@@ -95,4 +204,21 @@ mod tests {
             super::turbo_stream_target("custom", "target", &mut attributes, "")
         );
     }
+
+    #[test]
+    fn dom_id() {
+        assert_eq!("clearance_5", super::dom_id("clearance", Some(5), None));
+        assert_eq!(
+            "edit_clearance_5",
+            super::dom_id("clearance", Some(5), Some("edit"))
+        );
+        assert_eq!(
+            "new_clearance",
+            super::dom_id("clearance", None::<u32>, None)
+        );
+        assert_eq!(
+            "custom_clearance",
+            super::dom_id("clearance", None::<u32>, Some("custom"))
+        );
+    }
 }
@@ -0,0 +1,144 @@
+/// Concatenates a sequence of Turbo Stream actions into a single payload.
+///
+/// Each statement is an action name from [`stream`](crate::stream) or [`power`](crate::power),
+/// followed by its comma separated arguments and, for actions that carry template content,
+/// `=> content`. Statements expand into calls on the helpers of the same name. Custom
+/// [`TurboAction`](crate::TurboAction)s are included with `action &value`.
+///
+/// ```
+/// use hotwire_turbo::{power, stream, turbo_stream};
+///
+/// let html = "<p>Hello</p>";
+/// let payload = turbo_stream! {
+///     remove "msg_1";
+///     append "messages" => html;
+///     set_focus "#input";
+/// };
+///
+/// assert_eq!(
+///     payload,
+///     format!(
+///         "{}{}{}",
+///         stream::remove("msg_1"),
+///         stream::append("messages", html),
+///         power::set_focus("#input")
+///     )
+/// );
+/// ```
+///
+/// Unknown actions fail to compile, pointing at the action name:
+///
+/// ```compile_fail
+/// let payload = hotwire_turbo::turbo_stream! { explode "msg_1"; };
+/// ```
+///
+/// `action` only takes a reference to a custom action, it isn't a stream action itself:
+///
+/// ```compile_fail
+/// let payload = hotwire_turbo::turbo_stream! { action("msg_1"); };
+/// ```
+#[macro_export]
+macro_rules! turbo_stream {
+    ($($statements:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut output = ::std::string::String::new();
+        $crate::__turbo_stream_statements!(output; $($statements)*);
+        output
+    }};
+}
+
+/// Pushes the rendering of each `turbo_stream!` statement onto `$output`, one at a time.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __turbo_stream_statements {
+    ($output:ident; $(;)?) => {};
+    ($output:ident; action & $value:expr $(; $($rest:tt)*)?) => {
+        $output.push_str(&$crate::__private::action(&$value));
+        $crate::__turbo_stream_statements!($output; $($($rest)*)?);
+    };
+    ($output:ident; $action:ident $($argument:expr),* $(=> $content:expr)? $(; $($rest:tt)*)?) => {
+        $output.push_str(&$crate::__private::actions::$action($($argument,)* $($content)?));
+        $crate::__turbo_stream_statements!($output; $($($rest)*)?);
+    };
+}
+
+/// Renders a sequence of Turbo Stream actions into a `&'static str` at compile time.
+///
+/// Takes the same statements as [`turbo_stream!`], but every argument must be a constant
+/// expression, such as a string literal. See the [`constant`](crate::constant) module for the
+/// supported actions.
+///
+/// ```
+/// use hotwire_turbo::{power, static_turbo_stream};
+///
+/// const RELOAD: &str = static_turbo_stream! { reload };
+/// assert_eq!(power::reload(), RELOAD);
+/// ```
+#[macro_export]
+macro_rules! static_turbo_stream {
+    ($($action:ident $($argument:expr),* $(=> $content:expr)?);* $(;)?) => {{
+        const TAGS: &[$crate::constant::Tag] = &[
+            $($crate::constant::$action($($argument,)* $($content)?)),*
+        ];
+        const LEN: usize = $crate::constant::len(TAGS);
+        const BYTES: [u8; LEN] = $crate::constant::render::<LEN>(TAGS);
+        const PAYLOAD: &str = $crate::constant::as_str(&BYTES);
+        PAYLOAD
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{power, stream};
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn empty() {
+        assert_eq!("", turbo_stream! {});
+    }
+
+    #[test]
+    fn without_arguments() {
+        assert_eq!(power::reload(), turbo_stream! { reload });
+    }
+
+    #[test]
+    fn sequence() {
+        let html = String::from("<p>Hello</p>");
+        let expected = format!(
+            "{}{}{}{}",
+            stream::remove("msg_1"),
+            stream::append("messages", &html),
+            power::replace_css_class("#element", "one", "two"),
+            power::history_go(2)
+        );
+        assert_eq!(
+            expected,
+            turbo_stream! {
+                remove "msg_1";
+                append "messages" => &html;
+                replace_css_class "#element", "one", "two";
+                history_go 2;
+            }
+        );
+    }
+
+    #[test]
+    fn expression_arguments() {
+        let target = format!("message_{}", 1);
+        let mut options = BTreeMap::from([("icon", "/icon.png")]);
+        let expected = format!(
+            "{}{}",
+            stream::replace(&target, "Test"),
+            power::notification("title", &mut options.clone(), "body")
+        );
+        assert_eq!(
+            expected,
+            turbo_stream! {
+                replace &target => "Test";
+                notification "title", &mut options => "body";
+            }
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! Broadcasts stream payloads through a [Mercure](https://mercure.rocks) hub, as Symfony UX Turbo
+//! does.
+//!
+//! [`PublishRequestBuilder`] signs and encodes updates into a [`PublishRequest`]. This crate
+//! doesn't send it: that is left to the HTTP client the application already uses.
+//!
+//! ```
+//! use hotwire_turbo::mercure::{PublishRequestBuilder, Update};
+//! use hotwire_turbo::stream;
+//!
+//! let builder = PublishRequestBuilder::new(
+//!     "https://example.com/.well-known/mercure",
+//!     b"!ChangeThisMercureHubJWTSecretKey!",
+//! );
+//! let update = Update::new("https://example.com/books/1", stream::replace("book_1", "<p>Dune</p>"))
+//!     .private();
+//! let request = builder.build(&update);
+//!
+//! assert_eq!("https://example.com/.well-known/mercure", request.url);
+//! assert!(request.authorization.starts_with("Bearer ey"));
+//! assert_eq!(
+//!     "topic=https%3A%2F%2Fexample.com%2Fbooks%2F1&data=%3Cturbo-stream+action%3D%22replace%22+target%3D%22book_1%22%3E%3Ctemplate%3E%3Cp%3EDune%3C%2Fp%3E%3C%2Ftemplate%3E%3C%2Fturbo-stream%3E&private=on",
+//!     request.body
+//! );
+//! ```
+//!
+//! Topic selectors are matched exactly, with `*` matching every topic. URI templates aren't
+//! supported.
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The path hubs are served at.
+pub const PATH: &str = "/.well-known/mercure";
+
+/// The content type of publish requests.
+pub const CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// The cookie subscribers can send their token in, as `EventSource` can't set headers.
+pub const COOKIE: &str = "mercureAuthorization";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The token is malformed, not HS256 or has an invalid signature.
+    InvalidToken(String),
+    /// The token's `exp` claim is in the past.
+    ExpiredToken,
+    /// The publish request body isn't a valid update.
+    InvalidUpdate(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidToken(reason) => write!(f, "invalid Mercure token: {reason}"),
+            Error::ExpiredToken => f.write_str("expired Mercure token"),
+            Error::InvalidUpdate(reason) => write!(f, "invalid Mercure update: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Whether the topic selector matches `topic`.
+pub fn matches(selector: &str, topic: &str) -> bool {
+    selector == "*" || selector == topic
+}
+
+/// The `mercure` claim of a token: the topic selectors it may publish and subscribe to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Claims {
+    publish: Vec<String>,
+    subscribe: Vec<String>,
+    expires_at: Option<u64>,
+}
+
+impl Claims {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn publish(mut self, selector: &str) -> Self {
+        self.publish.push(selector.to_string());
+        self
+    }
+
+    pub fn subscribe(mut self, selector: &str) -> Self {
+        self.subscribe.push(selector.to_string());
+        self
+    }
+
+    /// Sets the `exp` claim, in seconds since the Unix epoch.
+    pub fn expires_at(mut self, seconds: u64) -> Self {
+        self.expires_at = Some(seconds);
+        self
+    }
+
+    pub fn can_publish(&self, topic: &str) -> bool {
+        self.publish.iter().any(|selector| matches(selector, topic))
+    }
+
+    pub fn can_subscribe(&self, topic: &str) -> bool {
+        self.subscribe
+            .iter()
+            .any(|selector| matches(selector, topic))
+    }
+
+    /// Signs the claims as an HS256 JWT.
+    pub fn sign(&self, secret: &[u8]) -> String {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+        let mut claims = json!({
+            "mercure": { "publish": self.publish, "subscribe": self.subscribe }
+        });
+        if let Some(expires_at) = self.expires_at {
+            claims["exp"] = expires_at.into();
+        }
+        let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let signature =
+            URL_SAFE_NO_PAD.encode(mac(secret, &header, &claims).finalize().into_bytes());
+        format!("{header}.{claims}.{signature}")
+    }
+
+    /// Verifies an HS256 JWT signed with `secret` and returns its claims.
+    pub fn verify(secret: &[u8], token: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidToken(reason.to_string());
+        let mut parts = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("not a JWT"));
+        };
+        let decode = |part: &str| -> Result<Value, Error> {
+            let json = URL_SAFE_NO_PAD
+                .decode(part)
+                .map_err(|_| invalid("invalid base64"))?;
+            serde_json::from_slice(&json).map_err(|_| invalid("invalid JSON"))
+        };
+        if decode(header)?.get("alg").and_then(Value::as_str) != Some("HS256") {
+            return Err(invalid("unsupported algorithm"));
+        }
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| invalid("invalid base64"))?;
+        mac(secret, header, claims)
+            .verify_slice(&signature)
+            .map_err(|_| invalid("invalid signature"))?;
+        let claims = decode(claims)?;
+        let expires_at = claims.get("exp").and_then(Value::as_u64);
+        if expires_at.is_some_and(|expires_at| expires_at <= now()) {
+            return Err(Error::ExpiredToken);
+        }
+        let selectors = |key: &str| -> Vec<String> {
+            claims
+                .pointer(&format!("/mercure/{key}"))
+                .and_then(Value::as_array)
+                .map(|selectors| {
+                    selectors
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Ok(Claims {
+            publish: selectors("publish"),
+            subscribe: selectors("subscribe"),
+            expires_at,
+        })
+    }
+}
+
+fn mac(secret: &[u8], header: &str, claims: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(format!("{header}.{claims}").as_bytes());
+    mac
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+/// An update published to one or more topics, usually a rendered stream payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Update {
+    topics: Vec<String>,
+    data: String,
+    private: bool,
+    id: Option<String>,
+    event_type: Option<String>,
+    retry: Option<u64>,
+}
+
+impl Update {
+    pub fn new(topic: &str, data: impl Into<String>) -> Self {
+        Update {
+            topics: vec![topic.to_string()],
+            data: data.into(),
+            private: false,
+            id: None,
+            event_type: None,
+            retry: None,
+        }
+    }
+
+    /// Also publishes the update to `topic`.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topics.push(topic.to_string());
+        self
+    }
+
+    /// Only delivers the update to subscribers authorized for one of its topics.
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+
+    /// Sets the id, which the hub otherwise generates.
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Sets the SSE event type. Turbo's `<turbo-stream-source>` only handles the default,
+    /// `message`.
+    pub fn with_event_type(mut self, event_type: &str) -> Self {
+        self.event_type = Some(event_type.to_string());
+        self
+    }
+
+    /// Sets the reconnection delay sent to subscribers, in milliseconds.
+    pub fn with_retry(mut self, milliseconds: u64) -> Self {
+        self.retry = Some(milliseconds);
+        self
+    }
+
+    pub fn topics(&self) -> &[String] {
+        &self.topics
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn event_type(&self) -> Option<&str> {
+        self.event_type.as_deref()
+    }
+
+    pub fn retry(&self) -> Option<u64> {
+        self.retry
+    }
+
+    /// Encodes the update as a publish request body.
+    pub fn to_form(&self) -> String {
+        let mut pairs: Vec<(&str, String)> = self
+            .topics
+            .iter()
+            .map(|topic| ("topic", topic.clone()))
+            .collect();
+        pairs.push(("data", self.data.clone()));
+        if self.private {
+            pairs.push(("private", "on".to_string()));
+        }
+        if let Some(id) = &self.id {
+            pairs.push(("id", id.clone()));
+        }
+        if let Some(event_type) = &self.event_type {
+            pairs.push(("type", event_type.clone()));
+        }
+        if let Some(retry) = self.retry {
+            pairs.push(("retry", retry.to_string()));
+        }
+        pairs
+            .iter()
+            .map(|(name, value)| format!("{name}={}", form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Decodes a publish request body.
+    pub fn from_form(body: &str) -> Result<Self, Error> {
+        let mut update = Update::new("", "");
+        update.topics.clear();
+        for (name, value) in form_pairs(body) {
+            match name.as_str() {
+                "topic" => update.topics.push(value),
+                "data" => update.data = value,
+                "private" => update.private = !value.is_empty(),
+                "id" => update.id = Some(event_field("id", value)?),
+                "type" => update.event_type = Some(event_field("type", value)?),
+                "retry" => {
+                    let retry = value
+                        .parse()
+                        .map_err(|_| Error::InvalidUpdate(format!("invalid retry `{value}`")))?;
+                    update.retry = Some(retry);
+                }
+                _ => {}
+            }
+        }
+        if update.topics.is_empty() {
+            return Err(Error::InvalidUpdate("missing `topic`".to_string()));
+        }
+        Ok(update)
+    }
+}
+
+/// Server-sent event fields end at a line break, and an event id can't contain NUL, so `id` and
+/// `type` can't contain either.
+fn event_field(name: &str, value: String) -> Result<String, Error> {
+    if value.contains(['\r', '\n', '\0']) {
+        return Err(Error::InvalidUpdate(format!(
+            "line break or NUL in `{name}`"
+        )));
+    }
+    Ok(value)
+}
+
+/// Decodes `application/x-www-form-urlencoded` pairs, e.g. the `topic` query parameters of a
+/// subscription.
+pub fn form_pairs(input: &str) -> Vec<(String, String)> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (form_decode(name), form_decode(value))
+        })
+        .collect()
+}
+
+fn form_encode(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                output.push(byte as char)
+            }
+            b' ' => output.push('+'),
+            byte => output.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    output
+}
+
+fn form_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => output.push(b' '),
+            b'%' => {
+                let hex = value
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        output.push(byte);
+                        index += 2;
+                    }
+                    None => output.push(b'%'),
+                }
+            }
+            byte => output.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// A publish request, to send as a `POST` with any HTTP client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublishRequest {
+    pub url: String,
+    /// The `Authorization` header.
+    pub authorization: String,
+    /// The body, of type [`CONTENT_TYPE`].
+    pub body: String,
+}
+
+/// Builds the requests publishing updates to a hub. Sending them is up to the caller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublishRequestBuilder {
+    hub_url: String,
+    token: String,
+}
+
+impl PublishRequestBuilder {
+    /// Requests allowed to publish to every topic, signing their token with `secret`.
+    pub fn new(hub_url: &str, secret: &[u8]) -> Self {
+        Self::with_token(hub_url, &Claims::new().publish("*").sign(secret))
+    }
+
+    /// Requests using a token issued elsewhere.
+    pub fn with_token(hub_url: &str, token: &str) -> Self {
+        PublishRequestBuilder {
+            hub_url: hub_url.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// The signed request publishing `update`, to send as a `POST`.
+    pub fn build(&self, update: &Update) -> PublishRequest {
+        PublishRequest {
+            url: self.hub_url.clone(),
+            authorization: format!("Bearer {}", self.token),
+            body: update.to_form(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SECRET: &[u8] = b"secret";
+
+    #[test]
+    fn sign_and_verify() {
+        let claims = Claims::new()
+            .publish("*")
+            .subscribe("https://example.com/books/1");
+        let token = claims.sign(SECRET);
+        assert_eq!(
+            "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9",
+            token.split('.').next().unwrap()
+        );
+        let verified = Claims::verify(SECRET, &token).unwrap();
+        assert_eq!(claims, verified);
+        assert!(verified.can_publish("https://example.com/books/2"));
+        assert!(verified.can_subscribe("https://example.com/books/1"));
+        assert!(!verified.can_subscribe("https://example.com/books/2"));
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let token = Claims::new().publish("*").sign(SECRET);
+        assert_eq!(
+            Err(Error::InvalidToken("invalid signature".to_string())),
+            Claims::verify(b"other", &token)
+        );
+        assert_eq!(
+            Err(Error::InvalidToken("not a JWT".to_string())),
+            Claims::verify(SECRET, "abc")
+        );
+        let expired = Claims::new().expires_at(1).sign(SECRET);
+        assert_eq!(Err(Error::ExpiredToken), Claims::verify(SECRET, &expired));
+    }
+
+    #[test]
+    fn form_round_trip() {
+        let update = Update::new("/books/1", "<p>a & b</p>")
+            .topic("/books")
+            .private()
+            .with_id("urn:1")
+            .with_event_type("book")
+            .with_retry(1000);
+        assert_eq!(
+            "topic=%2Fbooks%2F1&topic=%2Fbooks&data=%3Cp%3Ea+%26+b%3C%2Fp%3E&private=on&id=urn%3A1&type=book&retry=1000",
+            update.to_form()
+        );
+        assert_eq!(Ok(update.clone()), Update::from_form(&update.to_form()));
+    }
+
+    #[test]
+    fn invalid_updates() {
+        assert_eq!(
+            Err(Error::InvalidUpdate("missing `topic`".to_string())),
+            Update::from_form("data=x")
+        );
+        assert_eq!(
+            Err(Error::InvalidUpdate("invalid retry `soon`".to_string())),
+            Update::from_form("topic=a&retry=soon")
+        );
+        assert_eq!(
+            Err(Error::InvalidUpdate(
+                "line break or NUL in `id`".to_string()
+            )),
+            Update::from_form("topic=a&id=1%0D%0Aevent%3A+x")
+        );
+        assert_eq!(
+            Err(Error::InvalidUpdate(
+                "line break or NUL in `type`".to_string()
+            )),
+            Update::from_form("topic=a&type=book%0A")
+        );
+        assert_eq!(
+            Err(Error::InvalidUpdate(
+                "line break or NUL in `id`".to_string()
+            )),
+            Update::from_form("topic=a&id=%00")
+        );
+    }
+
+    #[test]
+    fn decode_pairs() {
+        assert_eq!(
+            vec![
+                ("topic".to_string(), "a b".to_string()),
+                ("topic".to_string(), "100%".to_string()),
+                ("flag".to_string(), "".to_string()),
+            ],
+            form_pairs("topic=a+b&topic=100%&flag")
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! `<meta>` tags that configure Turbo from the document `<head>`.
+//!
+//! The same [`Meta`] values can be changed at runtime with [`power::set_meta`].
+//!
+//! [`power::set_meta`]: crate::power::set_meta
+use crate::escape_attribute;
+
+/// `turbo-visit-control`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisitControl {
+    /// Always perform a full page load when visiting the page.
+    Reload,
+}
+
+/// `turbo-cache-control`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheControl {
+    /// Never cache the page.
+    NoCache,
+    /// Cache the page, but don't show it as a preview.
+    NoPreview,
+}
+
+/// `turbo-refresh-method`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefreshMethod {
+    Replace,
+    Morph,
+}
+
+/// `turbo-refresh-scroll`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefreshScroll {
+    Reset,
+    Preserve,
+}
+
+/// `view-transition`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewTransition {
+    SameOrigin,
+}
+
+/// A Turbo `<meta>` tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Meta<'a> {
+    VisitControl(VisitControl),
+    CacheControl(CacheControl),
+    RefreshMethod(RefreshMethod),
+    RefreshScroll(RefreshScroll),
+    /// Scopes Turbo Drive to the paths below the root.
+    Root(&'a str),
+    /// Enables or disables link prefetching.
+    Prefetch(bool),
+    ViewTransition(ViewTransition),
+    /// The nonce Turbo uses for the scripts and styles it inserts.
+    CspNonce(&'a str),
+    /// Any other `<meta>` tag, e.g. `viewport`.
+    Other {
+        name: &'a str,
+        content: &'a str,
+    },
+}
+
+impl<'a> From<(&'a str, &'a str)> for Meta<'a> {
+    /// A `(name, content)` pair as [`Meta::Other`].
+    fn from((name, content): (&'a str, &'a str)) -> Self {
+        Meta::Other { name, content }
+    }
+}
+
+impl<'a> Meta<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            Meta::VisitControl(_) => "turbo-visit-control",
+            Meta::CacheControl(_) => "turbo-cache-control",
+            Meta::RefreshMethod(_) => "turbo-refresh-method",
+            Meta::RefreshScroll(_) => "turbo-refresh-scroll",
+            Meta::Root(_) => "turbo-root",
+            Meta::Prefetch(_) => "turbo-prefetch",
+            Meta::ViewTransition(_) => "view-transition",
+            Meta::CspNonce(_) => "csp-nonce",
+            Meta::Other { name, .. } => name,
+        }
+    }
+
+    pub fn content(&self) -> &'a str {
+        match self {
+            Meta::VisitControl(VisitControl::Reload) => "reload",
+            Meta::CacheControl(CacheControl::NoCache) => "no-cache",
+            Meta::CacheControl(CacheControl::NoPreview) => "no-preview",
+            Meta::RefreshMethod(RefreshMethod::Replace) => "replace",
+            Meta::RefreshMethod(RefreshMethod::Morph) => "morph",
+            Meta::RefreshScroll(RefreshScroll::Reset) => "reset",
+            Meta::RefreshScroll(RefreshScroll::Preserve) => "preserve",
+            Meta::Root(root) => root,
+            Meta::Prefetch(true) => "true",
+            Meta::Prefetch(false) => "false",
+            Meta::ViewTransition(ViewTransition::SameOrigin) => "same-origin",
+            Meta::CspNonce(nonce) => nonce,
+            Meta::Other { content, .. } => content,
+        }
+    }
+
+    /// Renders the `<meta>` tag.
+    pub fn render(&self) -> String {
+        format!(
+            r#"<meta name="{}" content="{}">"#,
+            escape_attribute(self.name()),
+            escape_attribute(self.content())
+        )
+    }
+}
+
+pub fn visit_control(value: VisitControl) -> String {
+    Meta::VisitControl(value).render()
+}
+
+pub fn cache_control(value: CacheControl) -> String {
+    Meta::CacheControl(value).render()
+}
+
+pub fn refresh_method(value: RefreshMethod) -> String {
+    Meta::RefreshMethod(value).render()
+}
+
+pub fn refresh_scroll(value: RefreshScroll) -> String {
+    Meta::RefreshScroll(value).render()
+}
+
+pub fn root(path: &str) -> String {
+    Meta::Root(path).render()
+}
+
+pub fn prefetch(enabled: bool) -> String {
+    Meta::Prefetch(enabled).render()
+}
+
+pub fn view_transition(value: ViewTransition) -> String {
+    Meta::ViewTransition(value).render()
+}
+
+pub fn csp_nonce(nonce: &str) -> String {
+    Meta::CspNonce(nonce).render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn visit_control() {
+        let expected = r#"<meta name="turbo-visit-control" content="reload">"#;
+        assert_eq!(expected, super::visit_control(VisitControl::Reload));
+    }
+
+    #[test]
+    fn cache_control() {
+        let expected = r#"<meta name="turbo-cache-control" content="no-preview">"#;
+        assert_eq!(expected, super::cache_control(CacheControl::NoPreview));
+    }
+
+    #[test]
+    fn refresh_method() {
+        let expected = r#"<meta name="turbo-refresh-method" content="morph">"#;
+        assert_eq!(expected, super::refresh_method(RefreshMethod::Morph));
+    }
+
+    #[test]
+    fn refresh_scroll() {
+        let expected = r#"<meta name="turbo-refresh-scroll" content="preserve">"#;
+        assert_eq!(expected, super::refresh_scroll(RefreshScroll::Preserve));
+    }
+
+    #[test]
+    fn root() {
+        let expected = r#"<meta name="turbo-root" content="/app">"#;
+        assert_eq!(expected, super::root("/app"));
+    }
+
+    #[test]
+    fn root_is_escaped() {
+        let expected = r#"<meta name="turbo-root" content="/&quot;&gt;">"#;
+        assert_eq!(expected, super::root(r#"/">"#));
+    }
+
+    #[test]
+    fn prefetch() {
+        let expected = r#"<meta name="turbo-prefetch" content="false">"#;
+        assert_eq!(expected, super::prefetch(false));
+    }
+
+    #[test]
+    fn view_transition() {
+        let expected = r#"<meta name="view-transition" content="same-origin">"#;
+        assert_eq!(expected, super::view_transition(ViewTransition::SameOrigin));
+    }
+
+    #[test]
+    fn csp_nonce() {
+        let expected = r#"<meta name="csp-nonce" content="abc123">"#;
+        assert_eq!(expected, super::csp_nonce("abc123"));
+    }
+
+    #[test]
+    fn other_name_is_escaped() {
+        let meta = Meta::Other {
+            name: r#"x" http-equiv="refresh"#,
+            content: "0",
+        };
+        assert_eq!(
+            r#"<meta name="x&quot; http-equiv=&quot;refresh" content="0">"#,
+            meta.render()
+        );
+    }
+}
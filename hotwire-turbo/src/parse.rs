@@ -0,0 +1,303 @@
+//! Parses rendered `<turbo-stream>` payloads back into their actions.
+//!
+//! ```
+//! use hotwire_turbo::{parse, stream};
+//!
+//! let payload = stream::append("messages", "<p>Hi</p>") + &stream::remove("flash");
+//! let streams = parse::parse(&payload)?;
+//!
+//! assert_eq!("append", streams[0].action());
+//! assert_eq!(Some("messages"), streams[0].attribute("target"));
+//! assert_eq!("<p>Hi</p>", streams[0].content());
+//! assert_eq!("remove", streams[1].action());
+//! # Ok::<(), hotwire_turbo::parse::Error>(())
+//! ```
+use std::collections::BTreeMap;
+use std::fmt;
+
+const OPEN: &str = "<turbo-stream";
+const CLOSE: &str = "</turbo-stream>";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Text other than whitespace outside of a `<turbo-stream>` element, at the byte offset.
+    UnexpectedContent(usize),
+    /// A `<turbo-stream>` element without its end tag, starting at the byte offset.
+    Unclosed(usize),
+    /// A `<turbo-stream>` element without an `action` attribute, starting at the byte offset.
+    MissingAction(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedContent(position) => {
+                write!(f, "unexpected content at byte {position}")
+            }
+            Error::Unclosed(position) => {
+                write!(f, "unclosed <turbo-stream> element at byte {position}")
+            }
+            Error::MissingAction(position) => {
+                write!(
+                    f,
+                    "<turbo-stream> element without action at byte {position}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A single `<turbo-stream>` element.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stream {
+    action: String,
+    attributes: BTreeMap<String, String>,
+    content: String,
+}
+
+impl Stream {
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /// The unescaped value of the attribute `name`, `action` included.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    /// All attributes in name order, `action` included.
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+
+    /// The content of the `<template>`, or an empty string without one.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+/// Parses a payload of one or more `<turbo-stream>` elements.
+pub fn parse(payload: &str) -> Result<Vec<Stream>, Error> {
+    let mut streams = Vec::new();
+    let mut position = 0;
+    loop {
+        let rest = &payload[position..];
+        let trimmed = rest.trim_start();
+        if trimmed.is_empty() {
+            return Ok(streams);
+        }
+        position += rest.len() - trimmed.len();
+        if !is_open(trimmed) {
+            return Err(Error::UnexpectedContent(position));
+        }
+        let (stream, len) = element(trimmed).map_err(|error| offset(error, position))?;
+        streams.push(stream);
+        position += len;
+    }
+}
+
+/// Parses the element at the start of `input`, returning it with its length.
+fn element(input: &str) -> Result<(Stream, usize), Error> {
+    let (attributes, start_len) = start_tag(input, OPEN.len()).ok_or(Error::Unclosed(0))?;
+    let end = end_tag(input, start_len).ok_or(Error::Unclosed(0))?;
+    let inner = input[start_len..end].trim();
+    let content = match inner.strip_prefix("<template") {
+        Some(template) if template.starts_with(['>', ' ', '\t', '\n', '\r']) => {
+            let (_, template_start) =
+                start_tag(inner, "<template".len()).ok_or(Error::Unclosed(0))?;
+            // Only after the start tag, whose attribute values may hold `</template>` too.
+            let template_end = inner[template_start..]
+                .rfind("</template>")
+                .ok_or(Error::Unclosed(0))?;
+            inner[template_start..template_start + template_end].to_string()
+        }
+        _ => inner.to_string(),
+    };
+    let action = attributes
+        .get("action")
+        .cloned()
+        .ok_or(Error::MissingAction(0))?;
+    let stream = Stream {
+        action,
+        attributes,
+        content,
+    };
+    Ok((stream, end + CLOSE.len()))
+}
+
+/// Parses the attributes of the start tag whose name ends at `name_len`, returning them with
+/// the length of the tag.
+fn start_tag(input: &str, name_len: usize) -> Option<(BTreeMap<String, String>, usize)> {
+    let mut attributes = BTreeMap::new();
+    let mut position = name_len;
+    loop {
+        let rest = &input[position..];
+        let trimmed = rest.trim_start();
+        position += rest.len() - trimmed.len();
+        if let Some(rest) = trimmed.strip_prefix("/>").or(trimmed.strip_prefix('>')) {
+            return Some((attributes, input.len() - rest.len()));
+        }
+        let name_end = trimmed.find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))?;
+        if name_end == 0 {
+            position += 1;
+            continue;
+        }
+        let name = trimmed[..name_end].to_ascii_lowercase();
+        let rest = trimmed[name_end..].trim_start();
+        let (value, rest) = match rest.strip_prefix('=') {
+            Some(value) => attribute_value(value.trim_start())?,
+            None => ("", rest),
+        };
+        let value = html_escape::decode_html_entities(value).into_owned();
+        attributes.entry(name).or_insert(value);
+        position = input.len() - rest.len();
+    }
+}
+
+fn attribute_value(input: &str) -> Option<(&str, &str)> {
+    match input.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = input[1..].find(quote)? + 1;
+            Some((&input[1..end], &input[end + 1..]))
+        }
+        _ => {
+            let end = input
+                .find(|c: char| c.is_whitespace() || c == '>')
+                .unwrap_or(input.len());
+            Some((&input[..end], &input[end..]))
+        }
+    }
+}
+
+/// Finds the end tag matching the element whose content starts at `from`, skipping nested
+/// `<turbo-stream>` elements.
+fn end_tag(input: &str, from: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut position = from;
+    loop {
+        let next = input[position..].find('<')? + position;
+        let rest = &input[next..];
+        if rest.starts_with(CLOSE) {
+            if depth == 0 {
+                return Some(next);
+            }
+            depth -= 1;
+        } else if is_open(rest) {
+            depth += 1;
+        }
+        position = next + 1;
+    }
+}
+
+fn is_open(input: &str) -> bool {
+    input.starts_with(OPEN)
+        && input[OPEN.len()..].starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/')
+}
+
+fn offset(error: Error, position: usize) -> Error {
+    match error {
+        Error::UnexpectedContent(at) => Error::UnexpectedContent(at + position),
+        Error::Unclosed(at) => Error::Unclosed(at + position),
+        Error::MissingAction(at) => Error::MissingAction(at + position),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{power, stream};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rendered_actions() {
+        let payload = stream::replace("item_1", "<li>One</li>")
+            + &power::replace_css_class(".item", "a", "b")
+            + &power::history_back();
+        let streams = parse(&payload).unwrap();
+
+        assert_eq!(3, streams.len());
+        assert_eq!("replace", streams[0].action());
+        assert_eq!(Some("item_1"), streams[0].attribute("target"));
+        assert_eq!("<li>One</li>", streams[0].content());
+        assert_eq!(
+            vec![
+                ("action", "replace_css_class"),
+                ("from", "a"),
+                ("targets", ".item"),
+                ("to", "b")
+            ],
+            streams[1]
+                .attributes()
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("", streams[1].content());
+        assert_eq!("history_back", streams[2].action());
+    }
+
+    #[test]
+    fn attributes_are_unescaped() {
+        let payload = power::set_title(r#"Tom & "Jerry" <3"#);
+        let streams = parse(&payload).unwrap();
+        assert_eq!(Some(r#"Tom & "Jerry" <3"#), streams[0].attribute("title"));
+    }
+
+    #[test]
+    fn hand_written_markup() {
+        let payload = r#"
+            <turbo-stream action='append' target=messages data-flag>
+              <template>
+                <p>Hi</p>
+              </template>
+            </turbo-stream>
+        "#;
+        let streams = parse(payload).unwrap();
+        assert_eq!("append", streams[0].action());
+        assert_eq!(Some("messages"), streams[0].attribute("target"));
+        assert_eq!(Some(""), streams[0].attribute("data-flag"));
+        assert_eq!(
+            "\n                <p>Hi</p>\n              ",
+            streams[0].content()
+        );
+    }
+
+    #[test]
+    fn nested_streams_stay_in_content() {
+        let inner = stream::remove("flash");
+        let payload = stream::append("log", &inner);
+        let streams = parse(&payload).unwrap();
+        assert_eq!(1, streams.len());
+        assert_eq!(inner, streams[0].content());
+    }
+
+    #[test]
+    fn closing_tag_in_template_attribute() {
+        assert_eq!(
+            Err(Error::Unclosed(0)),
+            parse(
+                r#"<turbo-stream action="append" target="x"><template data-x="</template>"></turbo-stream>"#
+            )
+        );
+        let streams = parse(
+            r#"<turbo-stream action="append" target="x"><template data-x="</template>"><p>Hi</p></template></turbo-stream>"#,
+        )
+        .unwrap();
+        assert_eq!("<p>Hi</p>", streams[0].content());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Ok(vec![]), parse("  \n"));
+        assert_eq!(Err(Error::UnexpectedContent(1)), parse(" <p>Hi</p>"));
+        assert_eq!(
+            Err(Error::Unclosed(0)),
+            parse(r#"<turbo-stream action="remove" target="x"><template></template>"#)
+        );
+        let payload = stream::remove("x") + "<turbo-stream></turbo-stream>";
+        let position = stream::remove("x").len();
+        assert_eq!(Err(Error::MissingAction(position)), parse(&payload));
+    }
+}
@@ -0,0 +1,96 @@
+//! Records that render themselves, the counterpart of passing a record to the turbo-rails
+//! stream helpers.
+//!
+//! ```
+//! use hotwire_turbo::{dom_id, partial::Partial, stream};
+//!
+//! struct Clearance {
+//!     id: u32,
+//! }
+//!
+//! impl Partial for Clearance {
+//!     fn dom_id(&self) -> String {
+//!         dom_id("clearance", Some(self.id), None)
+//!     }
+//!
+//!     fn render(&self) -> String {
+//!         format!(r#"<div id="{}">Clearance</div>"#, self.dom_id())
+//!     }
+//! }
+//!
+//! let clearance = Clearance { id: 5 };
+//! assert_eq!(
+//!     stream::replace("clearance_5", r#"<div id="clearance_5">Clearance</div>"#),
+//!     stream::replace_partial(&clearance)
+//! );
+//! ```
+
+/// A record that knows its DOM id and how to render itself.
+pub trait Partial {
+    /// The id of the element the record renders, see [`dom_id`](crate::dom_id).
+    fn dom_id(&self) -> String;
+
+    /// Renders the record to HTML.
+    fn render(&self) -> String;
+}
+
+impl<P: Partial + ?Sized> Partial for &P {
+    fn dom_id(&self) -> String {
+        (**self).dom_id()
+    }
+
+    fn render(&self) -> String {
+        (**self).render()
+    }
+}
+
+/// One or more partials rendered into a single template.
+pub trait Partials {
+    fn render_all(&self) -> String;
+}
+
+impl<P: Partial> Partials for P {
+    fn render_all(&self) -> String {
+        self.render()
+    }
+}
+
+impl<P: Partial> Partials for [P] {
+    fn render_all(&self) -> String {
+        self.iter().map(Partial::render).collect()
+    }
+}
+
+impl<P: Partial> Partials for Vec<P> {
+    fn render_all(&self) -> String {
+        self.as_slice().render_all()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Partial, Partials};
+
+    pub(crate) struct Message(pub(crate) u32);
+
+    impl Partial for Message {
+        fn dom_id(&self) -> String {
+            crate::dom_id("message", Some(self.0), None)
+        }
+
+        fn render(&self) -> String {
+            format!(r#"<p id="{}">Message {}</p>"#, self.dom_id(), self.0)
+        }
+    }
+
+    #[test]
+    fn render_all() {
+        let expected = r#"<p id="message_1">Message 1</p><p id="message_2">Message 2</p>"#;
+        assert_eq!(
+            r#"<p id="message_1">Message 1</p>"#,
+            Message(1).render_all()
+        );
+        assert_eq!(expected, vec![Message(1), Message(2)].render_all());
+        assert_eq!(expected, [Message(1), Message(2)][..].render_all());
+    }
+}
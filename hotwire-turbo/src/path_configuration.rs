@@ -0,0 +1,327 @@
+//! [Hotwire Native path configuration](https://native.hotwired.dev/overview/path-configuration),
+//! the JSON rules the apps match visited URLs against.
+//!
+//! ```
+//! use hotwire_turbo::path_configuration::{Context, PathConfiguration, Rule};
+//!
+//! let configuration = PathConfiguration::new()
+//!     .rule(Rule::new(&[".*"]).context(Context::Default).pull_to_refresh_enabled(true))
+//!     .rule(Rule::new(&["/new$", "/edit$"]).context(Context::Modal).pull_to_refresh_enabled(false));
+//!
+//! assert_eq!(
+//!     r#"{"rules":[{"patterns":[".*"],"properties":{"context":"default","pull_to_refresh_enabled":true}},{"patterns":["/new$","/edit$"],"properties":{"context":"modal","pull_to_refresh_enabled":false}}],"settings":{}}"#,
+//!     configuration.to_json()?
+//! );
+//! # Ok::<(), hotwire_turbo::path_configuration::Error>(())
+//! ```
+//!
+//! Rules are applied in order, later matches overriding the properties of earlier ones.
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The configuration isn't valid JSON.
+    InvalidJson(String),
+    /// The JSON doesn't describe a valid configuration, e.g. a rule without patterns.
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidJson(reason) => write!(f, "invalid path configuration JSON: {reason}"),
+            Error::Invalid(reason) => write!(f, "invalid path configuration: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// How a matching screen is presented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Context {
+    Default,
+    Modal,
+}
+
+impl Context {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Context::Default => "default",
+            Context::Modal => "modal",
+        }
+    }
+}
+
+/// What a visit to a matching URL does to the navigation stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Presentation {
+    Default,
+    Push,
+    Pop,
+    Replace,
+    ReplaceRoot,
+    ClearAll,
+    Refresh,
+    None,
+}
+
+impl Presentation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Presentation::Default => "default",
+            Presentation::Push => "push",
+            Presentation::Pop => "pop",
+            Presentation::Replace => "replace",
+            Presentation::ReplaceRoot => "replace_root",
+            Presentation::ClearAll => "clear_all",
+            Presentation::Refresh => "refresh",
+            Presentation::None => "none",
+        }
+    }
+}
+
+const CONTEXTS: &[&str] = &["default", "modal"];
+const PRESENTATIONS: &[&str] = &[
+    "default",
+    "push",
+    "pop",
+    "replace",
+    "replace_root",
+    "clear_all",
+    "refresh",
+    "none",
+];
+
+/// Properties applied to the URLs matching any of the patterns, which are regular expressions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    patterns: Vec<String>,
+    properties: Map<String, Value>,
+}
+
+impl Rule {
+    pub fn new(patterns: &[&str]) -> Self {
+        Rule {
+            patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+            properties: Map::new(),
+        }
+    }
+
+    pub fn context(self, context: Context) -> Self {
+        self.property("context", context.as_str())
+    }
+
+    pub fn presentation(self, presentation: Presentation) -> Self {
+        self.property("presentation", presentation.as_str())
+    }
+
+    pub fn pull_to_refresh_enabled(self, enabled: bool) -> Self {
+        self.property("pull_to_refresh_enabled", enabled)
+    }
+
+    /// The native screen handling the URL, e.g. `hotwire://fragment/web` on Android.
+    pub fn uri(self, uri: &str) -> Self {
+        self.property("uri", uri)
+    }
+
+    /// Sets any other property, e.g. one read by the app's own code.
+    pub fn property(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.properties.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn properties(&self) -> &Map<String, Value> {
+        &self.properties
+    }
+
+    fn validate(&self, index: usize) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::Invalid(format!("rule {index}: {reason}")));
+        if self.patterns.is_empty() {
+            return invalid("no patterns".to_string());
+        }
+        if self.patterns.iter().any(String::is_empty) {
+            return invalid("empty pattern".to_string());
+        }
+        for (name, value) in &self.properties {
+            let valid = match name.as_str() {
+                "context" => value
+                    .as_str()
+                    .is_some_and(|value| CONTEXTS.contains(&value)),
+                "presentation" => value
+                    .as_str()
+                    .is_some_and(|value| PRESENTATIONS.contains(&value)),
+                "pull_to_refresh_enabled" => value.is_boolean(),
+                "uri" => value.is_string(),
+                _ => true,
+            };
+            if !valid {
+                return invalid(format!("invalid `{name}` {value}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The settings and rules served to the apps.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathConfiguration {
+    settings: Map<String, Value>,
+    rules: Vec<Rule>,
+}
+
+impl PathConfiguration {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets an app wide setting, e.g. a feature flag read by the app.
+    pub fn setting(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.settings.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn settings(&self) -> &Map<String, Value> {
+        &self.settings
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Checks every rule has patterns and the well known properties have valid values.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.rules
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, rule)| rule.validate(index))
+    }
+
+    /// Validates and serializes the configuration.
+    pub fn to_json(&self) -> Result<String, Error> {
+        self.validate()?;
+        let rules: Vec<Value> = self
+            .rules
+            .iter()
+            .map(|rule| json!({ "patterns": rule.patterns, "properties": rule.properties }))
+            .collect();
+        Ok(json!({ "settings": self.settings, "rules": rules }).to_string())
+    }
+
+    /// Parses and validates a configuration, e.g. one written by hand.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let configuration: Map<String, Value> =
+            serde_json::from_str(json).map_err(|error| Error::InvalidJson(error.to_string()))?;
+        let settings = match configuration.get("settings") {
+            None => Map::new(),
+            Some(Value::Object(settings)) => settings.clone(),
+            Some(_) => return Err(Error::Invalid("`settings` isn't an object".to_string())),
+        };
+        let Some(Value::Array(rules)) = configuration.get("rules") else {
+            return Err(Error::Invalid("`rules` isn't an array".to_string()));
+        };
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let invalid = |reason: &str| Error::Invalid(format!("rule {index}: {reason}"));
+                let patterns = rule
+                    .get("patterns")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid("`patterns` isn't an array"))?
+                    .iter()
+                    .map(|pattern| pattern.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid("`patterns` has a value that isn't a string"))?;
+                let properties = match rule.get("properties") {
+                    None => Map::new(),
+                    Some(Value::Object(properties)) => properties.clone(),
+                    Some(_) => return Err(invalid("`properties` isn't an object")),
+                };
+                Ok(Rule {
+                    patterns,
+                    properties,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let configuration = PathConfiguration { settings, rules };
+        configuration.validate()?;
+        Ok(configuration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn configuration() -> PathConfiguration {
+        PathConfiguration::new()
+            .setting("screenshots_enabled", true)
+            .rule(Rule::new(&[".*"]).uri("hotwire://fragment/web"))
+            .rule(
+                Rule::new(&["/new$"])
+                    .context(Context::Modal)
+                    .presentation(Presentation::Push)
+                    .property("title", "New"),
+            )
+    }
+
+    #[test]
+    fn to_json() {
+        let expected = r#"{"rules":[{"patterns":[".*"],"properties":{"uri":"hotwire://fragment/web"}},{"patterns":["/new$"],"properties":{"context":"modal","presentation":"push","title":"New"}}],"settings":{"screenshots_enabled":true}}"#;
+        assert_eq!(Ok(expected.to_string()), configuration().to_json());
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = configuration().to_json().unwrap();
+        assert_eq!(Ok(configuration()), PathConfiguration::from_json(&json));
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert_eq!(
+            Err(Error::Invalid("rule 0: no patterns".to_string())),
+            PathConfiguration::new().rule(Rule::new(&[])).to_json()
+        );
+        assert_eq!(
+            Err(Error::Invalid(
+                r#"rule 1: invalid `context` "sheet""#.to_string()
+            )),
+            PathConfiguration::from_json(
+                r#"{"rules":[{"patterns":[".*"]},{"patterns":["/new$"],"properties":{"context":"sheet"}}]}"#
+            )
+        );
+        assert_eq!(
+            Err(Error::Invalid(
+                "rule 0: invalid `pull_to_refresh_enabled` \"no\"".to_string()
+            )),
+            PathConfiguration::new()
+                .rule(Rule::new(&[".*"]).property("pull_to_refresh_enabled", "no"))
+                .validate()
+        );
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(matches!(
+            PathConfiguration::from_json("rules"),
+            Err(Error::InvalidJson(_))
+        ));
+        assert_eq!(
+            Err(Error::Invalid("`rules` isn't an array".to_string())),
+            PathConfiguration::from_json(r#"{"settings":{}}"#)
+        );
+    }
+}
@@ -1,3 +1,11 @@
+//! [Turbo Power](https://github.com/marcoroth/turbo_power) custom stream actions.
+//!
+//! Helpers for actions added after turbo_power 0.5 are behind the `turbo-power-0-6` feature, so
+//! applications on an older client can't emit them. The release each action appeared in is
+//! recorded in the [`registry`](crate::registry).
+//!
+//! Method calls use TurboBoost's `invoke` action, see [`boost::invoke`](crate::boost::invoke).
+use crate::meta::Meta;
 use crate::{turbo_stream_action, turbo_stream_target, turbo_stream_target_all};
 use std::collections::BTreeMap;
 
@@ -177,8 +185,10 @@ pub fn set_storage_local_item(key: &str, value: &str) -> String {
     set_storage_item(key, value, "local")
 }
 
-pub fn set_meta(name: &str, content: &str) -> String {
-    let mut attributes = BTreeMap::from([("name", name), ("content", content)]);
+/// Sets a typed Turbo [`Meta`] tag, or any other from a `(name, content)` pair.
+pub fn set_meta<'a>(meta: impl Into<Meta<'a>>) -> String {
+    let meta = meta.into();
+    let mut attributes = BTreeMap::from([("name", meta.name()), ("content", meta.content())]);
     turbo_stream_action("set_meta", &mut attributes, "")
 }
 
@@ -221,6 +231,16 @@ pub fn text_content(targets: &str, text: &str) -> String {
     turbo_stream_target_all("text_content", targets, &mut attributes, "")
 }
 
+/// Toggles the boolean `attribute`, or sets it to `force` when given.
+#[cfg(feature = "turbo-power-0-6")]
+pub fn toggle_attribute(targets: &str, attribute: &str, force: Option<bool>) -> String {
+    let mut attributes = BTreeMap::from([("attribute", attribute)]);
+    if let Some(force) = force {
+        attributes.insert("force", if force { "true" } else { "false" });
+    }
+    turbo_stream_target_all("toggle_attribute", targets, &mut attributes, "")
+}
+
 pub fn turbo_clear_cache() -> String {
     turbo_stream_action("turbo_clear_cache", &mut Default::default(), "")
 }
@@ -249,6 +269,7 @@ pub fn turbo_progress_bar_show() -> String {
 
 #[cfg(test)]
 mod tests {
+    use crate::meta::{Meta, RefreshMethod};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -521,7 +542,16 @@ mod tests {
     #[test]
     fn set_meta() {
         let expected = r#"<turbo-stream action="set_meta" content="initial-scale=1.0" name="viewport"><template></template></turbo-stream>"#;
-        assert_eq!(expected, super::set_meta("viewport", "initial-scale=1.0"));
+        assert_eq!(expected, super::set_meta(("viewport", "initial-scale=1.0")));
+    }
+
+    #[test]
+    fn set_meta_typed() {
+        let expected = r#"<turbo-stream action="set_meta" content="morph" name="turbo-refresh-method"><template></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            super::set_meta(Meta::RefreshMethod(RefreshMethod::Morph))
+        );
     }
 
     #[test]
@@ -587,6 +617,18 @@ mod tests {
         assert_eq!(expected, super::text_content("#element", "Text Content"));
     }
 
+    #[test]
+    #[cfg(feature = "turbo-power-0-6")]
+    fn toggle_attribute() {
+        let expected = r##"<turbo-stream action="toggle_attribute" attribute="hidden" targets="#menu"><template></template></turbo-stream>"##;
+        assert_eq!(expected, super::toggle_attribute("#menu", "hidden", None));
+        let expected = r##"<turbo-stream action="toggle_attribute" attribute="disabled" force="true" targets="#submit"><template></template></turbo-stream>"##;
+        assert_eq!(
+            expected,
+            super::toggle_attribute("#submit", "disabled", Some(true))
+        );
+    }
+
     #[test]
     fn turbo_clear_cache() {
         let expected =
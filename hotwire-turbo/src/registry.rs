@@ -0,0 +1,389 @@
+//! Static metadata for every action this crate renders.
+//!
+//! ```
+//! use hotwire_turbo::registry::{self, Origin, Selector};
+//!
+//! let action = registry::find("set_attribute").unwrap();
+//! assert_eq!(Origin::TurboPower, action.origin);
+//! assert_eq!(Selector::Targets, action.selector);
+//! assert_eq!(["attribute"], action.required);
+//!
+//! assert!(registry::validate(&hotwire_turbo::stream::remove("flash")).is_ok());
+//! ```
+use crate::compat::{Target, TurboVersion};
+use crate::parse::{self, Stream};
+use std::fmt;
+
+/// The project defining an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Origin {
+    Turbo,
+    TurboPower,
+    TurboBoost,
+}
+
+/// How an action selects the elements it operates on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Selector {
+    /// The `target` attribute, an element id.
+    Target,
+    /// The `targets` attribute, a CSS selector.
+    Targets,
+    /// Either `target` or `targets`.
+    Either,
+    /// The action doesn't operate on elements.
+    None,
+}
+
+/// A stream action and the attributes it understands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Action {
+    pub name: &'static str,
+    pub selector: Selector,
+    pub required: &'static [&'static str],
+    pub optional: &'static [&'static str],
+    /// Whether the action reads the content of its `<template>`.
+    pub template: bool,
+    pub origin: Origin,
+    /// The oldest client shipping the action.
+    pub since: Target,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(parse::Error),
+    UnknownAction(String),
+    MissingTarget {
+        action: &'static str,
+    },
+    MissingAttribute {
+        action: &'static str,
+        attribute: &'static str,
+    },
+    UnexpectedAttribute {
+        action: &'static str,
+        attribute: String,
+    },
+    /// Template content for an action that ignores it.
+    UnexpectedContent {
+        action: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => error.fmt(f),
+            Error::UnknownAction(action) => write!(f, "unknown action `{action}`"),
+            Error::MissingTarget { action } => write!(f, "`{action}` requires a target"),
+            Error::MissingAttribute { action, attribute } => {
+                write!(f, "`{action}` requires the `{attribute}` attribute")
+            }
+            Error::UnexpectedAttribute { action, attribute } => {
+                write!(f, "`{action}` doesn't take the `{attribute}` attribute")
+            }
+            Error::UnexpectedContent { action } => {
+                write!(f, "`{action}` doesn't take template content")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl Action {
+    /// Checks the stream's selector, attributes and content against this action. `data-*`
+    /// attributes are always allowed.
+    pub fn validate(&self, stream: &Stream) -> Result<(), Error> {
+        let has_target = stream.attribute("target").is_some();
+        let has_targets = stream.attribute("targets").is_some();
+        let selector_ok = match self.selector {
+            Selector::Target => has_target,
+            Selector::Targets => has_targets,
+            Selector::Either => has_target || has_targets,
+            Selector::None => true,
+        };
+        if !selector_ok {
+            return Err(Error::MissingTarget { action: self.name });
+        }
+        if let Some(attribute) = self
+            .required
+            .iter()
+            .find(|attribute| stream.attribute(attribute).is_none())
+        {
+            return Err(Error::MissingAttribute {
+                action: self.name,
+                attribute,
+            });
+        }
+        if let Some(attribute) = stream
+            .attributes()
+            .keys()
+            .find(|attribute| !self.accepts(attribute))
+        {
+            return Err(Error::UnexpectedAttribute {
+                action: self.name,
+                attribute: attribute.clone(),
+            });
+        }
+        if !self.template && !stream.content().trim().is_empty() {
+            return Err(Error::UnexpectedContent { action: self.name });
+        }
+        Ok(())
+    }
+
+    fn accepts(&self, attribute: &str) -> bool {
+        let selector = match self.selector {
+            Selector::Target => attribute == "target",
+            Selector::Targets => attribute == "targets",
+            Selector::Either => attribute == "target" || attribute == "targets",
+            Selector::None => false,
+        };
+        selector
+            || attribute == "action"
+            || attribute.starts_with("data-")
+            || self.required.contains(&attribute)
+            || self.optional.contains(&attribute)
+    }
+}
+
+/// The action named `name`.
+pub fn find(name: &str) -> Option<&'static Action> {
+    ACTIONS.iter().find(|action| action.name == name)
+}
+
+/// Validates every `<turbo-stream>` element of the payload against its action.
+pub fn validate(payload: &str) -> Result<(), Error> {
+    for stream in parse::parse(payload)? {
+        find(stream.action())
+            .ok_or_else(|| Error::UnknownAction(stream.action().to_string()))?
+            .validate(&stream)?;
+    }
+    Ok(())
+}
+
+const fn turbo(name: &'static str, template: bool, optional: &'static [&'static str]) -> Action {
+    Action {
+        name,
+        selector: Selector::Either,
+        required: &[],
+        optional,
+        template,
+        origin: Origin::Turbo,
+        since: Target::TURBO_7,
+    }
+}
+
+const fn power(
+    name: &'static str,
+    selector: Selector,
+    required: &'static [&'static str],
+    optional: &'static [&'static str],
+    template: bool,
+    minor: u32,
+) -> Action {
+    Action {
+        name,
+        selector,
+        required,
+        optional,
+        template,
+        origin: Origin::TurboPower,
+        since: Target::new(TurboVersion::Turbo7).with_power(minor),
+    }
+}
+
+const NOTIFICATION_OPTIONS: &[&str] = &[
+    "actions",
+    "badge",
+    "body",
+    "data",
+    "dir",
+    "icon",
+    "image",
+    "lang",
+    "renotify",
+    "require-interaction",
+    "silent",
+    "tag",
+    "vibrate",
+];
+
+/// Every action, Turbo's first, then Turbo Power's in name order, then TurboBoost's.
+#[rustfmt::skip]
+pub static ACTIONS: &[Action] = &[
+    turbo("after", true, &[]),
+    turbo("append", true, &[]),
+    turbo("before", true, &[]),
+    turbo("prepend", true, &[]),
+    Action {
+        selector: Selector::None,
+        since: Target::TURBO_8,
+        ..turbo("refresh", false, &["method", "request-id", "scroll"])
+    },
+    turbo("remove", false, &[]),
+    turbo("replace", true, &["method"]),
+    turbo("update", true, &["method"]),
+    power("add_css_class", Selector::Targets, &["classes"], &[], false, 1),
+    power("clear_storage", Selector::None, &[], &["type"], false, 1),
+    power("console_log", Selector::None, &["message"], &["level"], false, 1),
+    power("console_table", Selector::None, &["data"], &["columns"], false, 1),
+    power("dispatch_event", Selector::Targets, &["name"], &[], true, 1),
+    power("graft", Selector::Targets, &["parent"], &[], false, 1),
+    power("history_back", Selector::None, &[], &[], false, 1),
+    power("history_forward", Selector::None, &[], &[], false, 1),
+    power("history_go", Selector::None, &[], &["delta"], false, 1),
+    power("inner_html", Selector::Targets, &[], &[], true, 1),
+    power("insert_adjacent_html", Selector::Targets, &[], &["position"], true, 1),
+    power("insert_adjacent_text", Selector::Targets, &["text"], &["position"], false, 1),
+    power("morph", Selector::Targets, &[], &["children-only"], true, 1),
+    power("notification", Selector::None, &["title"], NOTIFICATION_OPTIONS, true, 1),
+    power("outer_html", Selector::Targets, &[], &[], true, 1),
+    power("push_state", Selector::None, &["url"], &["state", "title"], false, 1),
+    power("redirect_to", Selector::None, &["url"], &["turbo", "turbo-action", "turbo-frame"], false, 1),
+    power("reload", Selector::None, &[], &[], false, 1),
+    power("remove_attribute", Selector::Targets, &["attribute"], &[], false, 1),
+    power("remove_css_class", Selector::Targets, &["classes"], &[], false, 1),
+    power("remove_storage_item", Selector::None, &["key"], &["type"], false, 1),
+    power("replace_css_class", Selector::Targets, &["from", "to"], &[], false, 5),
+    power("replace_state", Selector::None, &["url"], &["state", "title"], false, 1),
+    power("reset_form", Selector::Targets, &[], &[], false, 5),
+    power("scroll_into_view", Selector::Targets, &[], &["align-to-top", "behavior", "block", "inline"], false, 1),
+    power("set_attribute", Selector::Targets, &["attribute"], &["value"], false, 1),
+    power("set_cookie", Selector::None, &["cookie"], &[], false, 3),
+    power("set_cookie_item", Selector::None, &["key"], &["value"], false, 3),
+    power("set_dataset_attribute", Selector::Targets, &["attribute"], &["value"], false, 1),
+    power("set_focus", Selector::Targets, &[], &[], false, 1),
+    power("set_meta", Selector::None, &["name"], &["content"], false, 1),
+    power("set_property", Selector::Targets, &["name"], &["value"], false, 1),
+    power("set_storage_item", Selector::None, &["key"], &["type", "value"], false, 1),
+    power("set_style", Selector::Targets, &["name"], &["value"], false, 1),
+    power("set_styles", Selector::Targets, &["styles"], &[], false, 1),
+    power("set_title", Selector::None, &["title"], &[], false, 1),
+    power("set_value", Selector::Targets, &[], &["value"], false, 1),
+    power("text_content", Selector::Targets, &[], &["text"], false, 1),
+    power("toggle_attribute", Selector::Targets, &["attribute"], &["force"], false, 6),
+    power("toggle_css_class", Selector::Targets, &["classes"], &[], false, 4),
+    power("turbo_clear_cache", Selector::None, &[], &[], false, 2),
+    power("turbo_frame_reload", Selector::Target, &[], &[], false, 2),
+    power("turbo_frame_set_src", Selector::Target, &["src"], &[], false, 2),
+    power("turbo_progress_bar_hide", Selector::None, &[], &[], false, 2),
+    power("turbo_progress_bar_set_value", Selector::None, &["value"], &[], false, 2),
+    power("turbo_progress_bar_show", Selector::None, &[], &[], false, 2),
+    // The `invoke` payload is JSON in the template, its target always `DOM`.
+    Action {
+        name: "invoke",
+        selector: Selector::Target,
+        required: &[],
+        optional: &[],
+        template: true,
+        origin: Origin::TurboBoost,
+        since: Target::TURBO_7.with_boost(),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{power, stream};
+    use pretty_assertions::assert_eq;
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    fn names_are_unique() {
+        let names: HashSet<_> = ACTIONS.iter().map(|action| action.name).collect();
+        assert_eq!(ACTIONS.len(), names.len());
+    }
+
+    #[test]
+    fn rendered_payloads_validate() {
+        let payloads = [
+            stream::append_all(".list", "<li>1</li>"),
+            stream::remove("flash"),
+            stream::replace("item_1", "<li>1</li>"),
+            power::add_css_class(".item", "active"),
+            power::console_table("[]", "a,b"),
+            power::dispatch_event("#el", "saved", "{}"),
+            power::history_go(2),
+            power::insert_adjacent_html("#list", "beforeend", "<li>1</li>"),
+            power::notification("Hi", &mut BTreeMap::from([("body", "There")]), ""),
+            power::push_state("/a", "A", "{}"),
+            power::redirect_to("/a", "replace", Some("main")),
+            power::replace_css_class(".item", "a", "b"),
+            power::set_meta(("theme", "dark")),
+            power::set_storage_item("a", "1", "local"),
+            power::turbo_frame_set_src("frame", "/frame"),
+            power::turbo_progress_bar_set_value("0.5"),
+        ];
+        for payload in payloads {
+            assert_eq!(Ok(()), validate(&payload), "{payload}");
+        }
+    }
+
+    #[test]
+    fn invalid_payloads() {
+        assert_eq!(
+            Err(Error::UnknownAction("confetti".to_string())),
+            validate(r#"<turbo-stream action="confetti"></turbo-stream>"#)
+        );
+        assert_eq!(
+            Err(Error::MissingTarget { action: "graft" }),
+            validate(r##"<turbo-stream action="graft" target="x" parent="#p"></turbo-stream>"##)
+        );
+        assert_eq!(
+            Err(Error::MissingAttribute {
+                action: "set_attribute",
+                attribute: "attribute"
+            }),
+            validate(r#"<turbo-stream action="set_attribute" targets=".x"></turbo-stream>"#)
+        );
+        assert_eq!(
+            Err(Error::UnexpectedAttribute {
+                action: "append",
+                attribute: "method".to_string()
+            }),
+            validate(r#"<turbo-stream action="append" target="x" method="morph"></turbo-stream>"#)
+        );
+        assert_eq!(
+            Err(Error::UnexpectedContent { action: "remove" }),
+            validate(
+                r#"<turbo-stream action="remove" target="x"><template><p>Hi</p></template></turbo-stream>"#
+            )
+        );
+    }
+
+    #[test]
+    fn data_attributes_are_allowed() {
+        assert_eq!(
+            Ok(()),
+            validate(
+                r#"<turbo-stream action="remove" target="x" data-source="chat"></turbo-stream>"#
+            )
+        );
+    }
+
+    #[test]
+    fn invoke() {
+        use crate::boost::{invoke, Receiver};
+        use crate::TurboAction;
+
+        let payload = invoke(Receiver::selector("#dialog"), "showModal").render();
+        assert_eq!(Ok(()), validate(&payload));
+        let action = find("invoke").unwrap();
+        assert_eq!(Origin::TurboBoost, action.origin);
+        assert_eq!(Target::TURBO_7.with_boost(), action.since);
+    }
+
+    #[test]
+    fn refresh() {
+        let refresh = find("refresh").unwrap();
+        assert_eq!(Selector::None, refresh.selector);
+        assert_eq!(Target::TURBO_8, refresh.since);
+    }
+}
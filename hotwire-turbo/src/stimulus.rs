@@ -0,0 +1,425 @@
+//! [Stimulus](https://stimulus.hotwired.dev) `data-*` attributes for server rendered markup.
+//!
+//! ```
+//! use hotwire_turbo::stimulus::{Action, Attributes, Controller};
+//!
+//! let modal = Controller::new("modal")?;
+//! let attributes = Attributes::new()
+//!     .controller(&modal)
+//!     .action(Action::new(&modal, "open")?.event("click"))
+//!     .target(&modal, "dialog")
+//!     .value(&modal, "url", "/modal")?;
+//!
+//! assert_eq!(
+//!     r#"data-action="click-&gt;modal#open" data-controller="modal" data-modal-target="dialog" data-modal-url-value="/modal""#,
+//!     attributes.to_string()
+//! );
+//! # Ok::<(), hotwire_turbo::stimulus::Error>(())
+//! ```
+use crate::escape_attribute;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Controller identifiers are lowercase words separated by `-`, or `--` for namespaces.
+    InvalidIdentifier(String),
+    /// Action methods are JavaScript identifiers.
+    InvalidMethod(String),
+    /// Value and class names are `camelCase` or `kebab-case` words of ASCII letters and digits.
+    InvalidName(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidIdentifier(identifier) => {
+                write!(f, "invalid Stimulus controller identifier `{identifier}`")
+            }
+            Error::InvalidMethod(method) => write!(f, "invalid Stimulus action method `{method}`"),
+            Error::InvalidName(name) => write!(f, "invalid Stimulus value or class name `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A validated Stimulus controller identifier, such as `modal` or `admin--user-list`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Controller(String);
+
+impl Controller {
+    pub fn new(identifier: &str) -> Result<Self, Error> {
+        let valid = identifier.split("--").all(|namespace| {
+            namespace.split('-').all(|word| {
+                word.starts_with(|c: char| c.is_ascii_lowercase())
+                    && word
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            })
+        });
+        if valid {
+            Ok(Controller(identifier.to_string()))
+        } else {
+            Err(Error::InvalidIdentifier(identifier.to_string()))
+        }
+    }
+
+    pub fn identifier(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Controller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Where a global action listens for its event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Global {
+    Window,
+    Document,
+}
+
+/// An action option, appended to the descriptor after a `:`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionOption {
+    Capture,
+    Once,
+    Passive,
+    NotPassive,
+    Stop,
+    Prevent,
+    OnlySelf,
+}
+
+impl ActionOption {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionOption::Capture => "capture",
+            ActionOption::Once => "once",
+            ActionOption::Passive => "passive",
+            ActionOption::NotPassive => "!passive",
+            ActionOption::Stop => "stop",
+            ActionOption::Prevent => "prevent",
+            ActionOption::OnlySelf => "self",
+        }
+    }
+}
+
+/// A Stimulus action descriptor, e.g. `click->modal#open:prevent`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Action {
+    event: Option<String>,
+    global: Option<Global>,
+    controller: Controller,
+    method: String,
+    options: Vec<ActionOption>,
+}
+
+impl Action {
+    /// An action calling `method` on `controller` for the element's default event.
+    pub fn new(controller: &Controller, method: &str) -> Result<Self, Error> {
+        let valid = method.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && method
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+        if !valid {
+            return Err(Error::InvalidMethod(method.to_string()));
+        }
+        Ok(Action {
+            event: None,
+            global: None,
+            controller: controller.clone(),
+            method: method.to_string(),
+            options: Vec::new(),
+        })
+    }
+
+    /// The event to listen for, including any key filter such as `keydown.enter`.
+    pub fn event(mut self, event: &str) -> Self {
+        self.event = Some(event.to_string());
+        self
+    }
+
+    /// Listens for the event on the window or document instead of the element.
+    pub fn global(mut self, global: Global) -> Self {
+        self.global = Some(global);
+        self
+    }
+
+    pub fn option(mut self, option: ActionOption) -> Self {
+        self.options.push(option);
+        self
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(event) = &self.event {
+            f.write_str(event)?;
+        }
+        match self.global {
+            Some(Global::Window) => f.write_str("@window")?,
+            Some(Global::Document) => f.write_str("@document")?,
+            None => {}
+        }
+        if self.event.is_some() || self.global.is_some() {
+            f.write_str("->")?;
+        }
+        write!(f, "{}#{}", self.controller, self.method)?;
+        for option in &self.options {
+            write!(f, ":{}", option.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of Stimulus attributes, rendered in name order by its `Display` implementation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attributes {
+    attributes: BTreeMap<String, Vec<String>>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the controller to `data-controller`.
+    pub fn controller(self, controller: &Controller) -> Self {
+        self.push("data-controller".to_string(), controller.to_string())
+    }
+
+    /// Adds the action to `data-action`.
+    pub fn action(self, action: Action) -> Self {
+        self.push("data-action".to_string(), action.to_string())
+    }
+
+    /// Adds `name` to `data-[controller]-target`.
+    pub fn target(self, controller: &Controller, name: &str) -> Self {
+        self.push(format!("data-{controller}-target"), name.to_string())
+    }
+
+    /// Sets `data-[controller]-[name]-value`. Strings are used as is, other values are
+    /// serialized as JSON.
+    pub fn value(
+        self,
+        controller: &Controller,
+        name: &str,
+        value: impl Into<Value>,
+    ) -> Result<Self, Error> {
+        let name = format!("data-{controller}-{}-value", kebab_case(name)?);
+        let value = match value.into() {
+            Value::String(value) => value,
+            value => value.to_string(),
+        };
+        Ok(self.set(name, value))
+    }
+
+    /// Sets `data-[controller]-[name]-class`.
+    pub fn class(self, controller: &Controller, name: &str, classes: &str) -> Result<Self, Error> {
+        let name = format!("data-{controller}-{}-class", kebab_case(name)?);
+        Ok(self.set(name, classes.to_string()))
+    }
+
+    /// Sets `data-[controller]-[outlet]-outlet` to the selector of the outlet's elements.
+    pub fn outlet(self, controller: &Controller, outlet: &Controller, selector: &str) -> Self {
+        self.set(
+            format!("data-{controller}-{outlet}-outlet"),
+            selector.to_string(),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    fn push(mut self, name: String, value: String) -> Self {
+        let values = self.attributes.entry(name).or_default();
+        if !values.contains(&value) {
+            values.push(value);
+        }
+        self
+    }
+
+    fn set(mut self, name: String, value: String) -> Self {
+        self.attributes.insert(name, vec![value]);
+        self
+    }
+}
+
+impl fmt::Display for Attributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, values)) in self.attributes.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, r#"{name}="{}""#, escape_attribute(&values.join(" ")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts `camelCase` value and class names to the `kebab-case` used in attribute names,
+/// failing on names that would need escaping or don't map back to the same property.
+fn kebab_case(name: &str) -> Result<String, Error> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .split('-')
+            .all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric()));
+    if !valid {
+        return Err(Error::InvalidName(name.to_string()));
+    }
+    let mut output = String::with_capacity(name.len() + 2);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            output.push('-');
+            output.push(c.to_ascii_lowercase());
+        } else {
+            output.push(c);
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn modal() -> Controller {
+        Controller::new("modal").unwrap()
+    }
+
+    #[test]
+    fn controller_identifiers() {
+        assert!(Controller::new("modal").is_ok());
+        assert!(Controller::new("user-list").is_ok());
+        assert!(Controller::new("admin--user-list2").is_ok());
+        assert_eq!(
+            Err(Error::InvalidIdentifier("".to_string())),
+            Controller::new("")
+        );
+        assert!(Controller::new("Modal").is_err());
+        assert!(Controller::new("user_list").is_err());
+        assert!(Controller::new("modal-").is_err());
+        assert!(Controller::new("admin---users").is_err());
+        assert!(Controller::new("2fa").is_err());
+        assert!(Controller::new(r#"modal" onclick="alert(1)"#).is_err());
+    }
+
+    #[test]
+    fn action_methods() {
+        assert!(Action::new(&modal(), "open").is_ok());
+        assert!(Action::new(&modal(), "_handle$1").is_ok());
+        assert_eq!(
+            Err(Error::InvalidMethod("open()".to_string())),
+            Action::new(&modal(), "open()")
+        );
+        assert!(Action::new(&modal(), "").is_err());
+    }
+
+    #[test]
+    fn action_descriptors() {
+        let action = Action::new(&modal(), "open").unwrap();
+        assert_eq!("modal#open", action.to_string());
+        assert_eq!(
+            "click->modal#open:prevent:stop",
+            action
+                .clone()
+                .event("click")
+                .option(ActionOption::Prevent)
+                .option(ActionOption::Stop)
+                .to_string()
+        );
+        assert_eq!(
+            "keydown.esc@window->modal#open",
+            action
+                .clone()
+                .event("keydown.esc")
+                .global(Global::Window)
+                .to_string()
+        );
+        assert_eq!(
+            "@document->modal#open:!passive",
+            action
+                .global(Global::Document)
+                .option(ActionOption::NotPassive)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn multiple_controllers_and_actions() {
+        let clipboard = Controller::new("clipboard").unwrap();
+        let expected = r#"data-action="click-&gt;modal#open click-&gt;clipboard#copy" data-controller="modal clipboard""#;
+        let attributes = Attributes::new()
+            .controller(&modal())
+            .controller(&clipboard)
+            .controller(&modal())
+            .action(Action::new(&modal(), "open").unwrap().event("click"))
+            .action(Action::new(&clipboard, "copy").unwrap().event("click"));
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn targets() {
+        let expected = r#"data-modal-target="dialog title""#;
+        let attributes = Attributes::new()
+            .target(&modal(), "dialog")
+            .target(&modal(), "title");
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn values() {
+        let expected = r#"data-modal-content-url-value="/modal?a=1&amp;b=2" data-modal-count-value="3" data-modal-open-value="true" data-modal-options-value="{&quot;size&quot;:&quot;lg&quot;}" data-modal-steps-value="[1,2]""#;
+        let attributes = Attributes::new()
+            .value(&modal(), "contentUrl", "/modal?a=1&b=2")
+            .and_then(|attributes| attributes.value(&modal(), "count", 3))
+            .and_then(|attributes| attributes.value(&modal(), "open", true))
+            .and_then(|attributes| attributes.value(&modal(), "options", json!({"size": "lg"})))
+            .and_then(|attributes| attributes.value(&modal(), "steps", json!([1, 2])))
+            .unwrap();
+        assert_eq!(expected, attributes.to_string());
+    }
+
+    #[test]
+    fn value_and_class_names() {
+        assert!(Attributes::new().value(&modal(), "content-url", 1).is_ok());
+        assert!(Attributes::new().class(&modal(), "isOpen2", "open").is_ok());
+        assert_eq!(
+            Err(Error::InvalidName(r#"url" onclick="alert(1)"#.to_string())),
+            Attributes::new().value(&modal(), r#"url" onclick="alert(1)"#, 1)
+        );
+        assert!(Attributes::new().value(&modal(), "", 1).is_err());
+        assert!(Attributes::new().value(&modal(), "2fa", 1).is_err());
+        assert!(Attributes::new().value(&modal(), "url-", 1).is_err());
+        assert!(Attributes::new()
+            .class(&modal(), "is open", "open")
+            .is_err());
+        assert!(Attributes::new()
+            .class(&modal(), "is_open", "open")
+            .is_err());
+    }
+
+    #[test]
+    fn classes_and_outlets() {
+        let expected =
+            r#"data-modal-active-class="is-open visible" data-modal-result-list-outlet=".results""#;
+        let attributes = Attributes::new()
+            .class(&modal(), "active", "is-open visible")
+            .unwrap()
+            .outlet(
+                &modal(),
+                &Controller::new("result-list").unwrap(),
+                ".results",
+            );
+        assert_eq!(expected, attributes.to_string());
+    }
+}
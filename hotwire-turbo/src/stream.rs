@@ -1,3 +1,4 @@
+use crate::partial::{Partial, Partials};
 use crate::{turbo_stream_target, turbo_stream_target_all};
 
 // Removes the <tt>target</tt> from the dom. The target can either be a dom id string or an object that responds to
@@ -180,8 +181,79 @@ pub fn prepend_all<S: AsRef<str>>(targets: &str, content: S) -> String {
     turbo_stream_target_all("prepend", targets, &mut Default::default(), content)
 }
 
+// Removes the element rendered by the <tt>partial</tt> from the dom, the equivalent of
+// <tt>turbo_stream.remove clearance</tt>.
+pub fn remove_partial<P: Partial + ?Sized>(partial: &P) -> String {
+    remove(partial.dom_id())
+}
+
+// Replaces the element rendered by the <tt>partial</tt> with a fresh rendering of it, the equivalent of
+// <tt>turbo_stream.replace clearance</tt>.
+pub fn replace_partial<P: Partial + ?Sized>(partial: &P) -> String {
+    replace(&partial.dom_id(), partial.render())
+}
+
+// Replaces the <tt>targets</tt> in the dom with the rendering of the <tt>partials</tt>.
+pub fn replace_all_partial<P: Partials + ?Sized>(targets: &str, partials: &P) -> String {
+    replace_all(targets, partials.render_all())
+}
+
+// Inserts the rendering of the <tt>partials</tt> before the <tt>target</tt> in the dom.
+pub fn before_partial<P: Partials + ?Sized>(target: &str, partials: &P) -> String {
+    before(target, partials.render_all())
+}
+
+// Inserts the rendering of the <tt>partials</tt> before the <tt>targets</tt> in the dom.
+pub fn before_all_partial<P: Partials + ?Sized>(targets: &str, partials: &P) -> String {
+    before_all(targets, partials.render_all())
+}
+
+// Inserts the rendering of the <tt>partials</tt> after the <tt>target</tt> in the dom.
+pub fn after_partial<P: Partials + ?Sized>(target: &str, partials: &P) -> String {
+    after(target, partials.render_all())
+}
+
+// Inserts the rendering of the <tt>partials</tt> after the <tt>targets</tt> in the dom.
+pub fn after_all_partial<P: Partials + ?Sized>(targets: &str, partials: &P) -> String {
+    after_all(targets, partials.render_all())
+}
+
+// Updates the content of the element rendered by the <tt>partial</tt> with a fresh rendering of it, the
+// equivalent of <tt>turbo_stream.update clearance</tt>.
+pub fn update_partial<P: Partial + ?Sized>(partial: &P) -> String {
+    update(&partial.dom_id(), partial.render())
+}
+
+// Updates the content of the <tt>targets</tt> in the dom with the rendering of the <tt>partials</tt>.
+pub fn update_all_partial<P: Partials + ?Sized>(targets: &str, partials: &P) -> String {
+    update_all(targets, partials.render_all())
+}
+
+// Appends the rendering of the <tt>partials</tt> to the <tt>target</tt> in the dom, the equivalent of
+// <tt>turbo_stream.append "clearances", clearance</tt>.
+pub fn append_partial<P: Partials + ?Sized>(target: &str, partials: &P) -> String {
+    append(target, partials.render_all())
+}
+
+// Appends the rendering of the <tt>partials</tt> to the <tt>targets</tt> in the dom.
+pub fn append_all_partial<P: Partials + ?Sized>(targets: &str, partials: &P) -> String {
+    append_all(targets, partials.render_all())
+}
+
+// Prepends the rendering of the <tt>partials</tt> to the <tt>target</tt> in the dom, the equivalent of
+// <tt>turbo_stream.prepend "clearances", clearance</tt>.
+pub fn prepend_partial<P: Partials + ?Sized>(target: &str, partials: &P) -> String {
+    prepend(target, partials.render_all())
+}
+
+// Prepends the rendering of the <tt>partials</tt> to the <tt>targets</tt> in the dom.
+pub fn prepend_all_partial<P: Partials + ?Sized>(targets: &str, partials: &P) -> String {
+    prepend_all(targets, partials.render_all())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::partial::tests::Message;
 
     #[test]
     fn empty_content() {
@@ -272,4 +344,45 @@ mod tests {
         let expected = r#"<turbo-stream action="prepend" targets=".message"><template>Test</template></turbo-stream>"#;
         assert_eq!(expected, super::prepend_all(".message", "Test"));
     }
+
+    #[test]
+    fn remove_partial() {
+        let expected = r#"<turbo-stream action="remove" target="message_1"><template></template></turbo-stream>"#;
+        assert_eq!(expected, super::remove_partial(&Message(1)));
+    }
+
+    #[test]
+    fn replace_partial() {
+        let expected = r#"<turbo-stream action="replace" target="message_1"><template><p id="message_1">Message 1</p></template></turbo-stream>"#;
+        assert_eq!(expected, super::replace_partial(&Message(1)));
+    }
+
+    #[test]
+    fn update_partial() {
+        let expected = r#"<turbo-stream action="update" target="message_1"><template><p id="message_1">Message 1</p></template></turbo-stream>"#;
+        assert_eq!(expected, super::update_partial(&Message(1)));
+    }
+
+    #[test]
+    fn append_partial() {
+        let expected = r#"<turbo-stream action="append" target="messages"><template><p id="message_1">Message 1</p></template></turbo-stream>"#;
+        assert_eq!(expected, super::append_partial("messages", &Message(1)));
+    }
+
+    #[test]
+    fn append_partial_collection() {
+        let expected = r#"<turbo-stream action="append" target="messages"><template><p id="message_1">Message 1</p><p id="message_2">Message 2</p></template></turbo-stream>"#;
+        let messages = vec![Message(1), Message(2)];
+        assert_eq!(expected, super::append_partial("messages", &messages));
+        assert_eq!(expected, super::append_partial("messages", &messages[..]));
+    }
+
+    #[test]
+    fn prepend_all_partial() {
+        let expected = r#"<turbo-stream action="prepend" targets=".messages"><template><p id="message_1">Message 1</p></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            super::prepend_all_partial(".messages", &Message(1))
+        );
+    }
 }
@@ -0,0 +1,583 @@
+//! [Tera](https://keats.github.io/tera/) functions and filters for rendering Turbo Stream tags
+//! from templates.
+//!
+//! Every `stream::*` and `power::*` helper is registered as a `turbo_stream_<action>` function
+//! whose named arguments match the Rust parameters, as is `turbo_stream_invoke(targets, method,
+//! args)` for [`boost::invoke`](crate::boost::invoke), e.g.
+//!
+//! ```text
+//! {{ turbo_stream_append(target="messages", content=message_html) }}
+//! {{ turbo_stream_set_focus(targets="#input") }}
+//! <div id="{{ message | dom_id(model="message") }}">
+//! ```
+//!
+//! Argument values that aren't strings are passed on as JSON, which suits the `detail`, `state`,
+//! `data` and `columns` arguments. All output is already escaped and marked safe.
+use crate::boost::{self, Invoke, Receiver};
+use crate::{escape_attribute, power, stream, TurboAction};
+use ::tera::{Error, Filter, Function, Result, Tera, Value};
+use std::collections::{BTreeMap, HashMap};
+
+type Args = HashMap<String, Value>;
+
+/// Registers the `turbo_stream_*` functions and the `dom_id` filter.
+pub fn register(tera: &mut Tera) {
+    register_stream(tera);
+    register_power(tera);
+    tera.register_filter("dom_id", DomId);
+}
+
+fn register_stream(tera: &mut Tera) {
+    function(tera, "remove", |args| {
+        Ok(stream::remove(string(args, "target")?))
+    });
+    function(tera, "remove_all", |args| {
+        Ok(stream::remove_all(&string(args, "targets")?))
+    });
+    function(tera, "replace", |args| {
+        Ok(stream::replace(&string(args, "target")?, content(args)?))
+    });
+    function(tera, "replace_all", |args| {
+        Ok(stream::replace_all(
+            &string(args, "targets")?,
+            content(args)?,
+        ))
+    });
+    function(tera, "before", |args| {
+        Ok(stream::before(&string(args, "target")?, content(args)?))
+    });
+    function(tera, "before_all", |args| {
+        Ok(stream::before_all(
+            &string(args, "targets")?,
+            content(args)?,
+        ))
+    });
+    function(tera, "after", |args| {
+        Ok(stream::after(&string(args, "target")?, content(args)?))
+    });
+    function(tera, "after_all", |args| {
+        Ok(stream::after_all(&string(args, "targets")?, content(args)?))
+    });
+    function(tera, "update", |args| {
+        Ok(stream::update(&string(args, "target")?, content(args)?))
+    });
+    function(tera, "update_all", |args| {
+        Ok(stream::update_all(
+            &string(args, "targets")?,
+            content(args)?,
+        ))
+    });
+    function(tera, "append", |args| {
+        Ok(stream::append(&string(args, "target")?, content(args)?))
+    });
+    function(tera, "append_all", |args| {
+        Ok(stream::append_all(
+            &string(args, "targets")?,
+            content(args)?,
+        ))
+    });
+    function(tera, "prepend", |args| {
+        Ok(stream::prepend(&string(args, "target")?, content(args)?))
+    });
+    function(tera, "prepend_all", |args| {
+        Ok(stream::prepend_all(
+            &string(args, "targets")?,
+            content(args)?,
+        ))
+    });
+}
+
+fn register_power(tera: &mut Tera) {
+    function(tera, "add_css_class", |args| {
+        Ok(power::add_css_class(
+            &string(args, "targets")?,
+            &string(args, "classes")?,
+        ))
+    });
+    function(tera, "toggle_css_class", |args| {
+        Ok(power::toggle_css_class(
+            &string(args, "targets")?,
+            &string(args, "classes")?,
+        ))
+    });
+    function(tera, "replace_css_class", |args| {
+        Ok(power::replace_css_class(
+            &string(args, "targets")?,
+            &string(args, "from")?,
+            &string(args, "to")?,
+        ))
+    });
+    function(tera, "invoke", |args| {
+        let invoke = boost::invoke(
+            Receiver::selector(&string(args, "targets")?),
+            &string(args, "method")?,
+        );
+        let invoke = match args.get("args") {
+            None => invoke,
+            Some(Value::Array(values)) => values.iter().cloned().fold(invoke, Invoke::arg),
+            Some(_) => return Err(Error::msg("`args` must be an array")),
+        };
+        Ok(invoke.render())
+    });
+    function(tera, "clear_local_storage", |_| {
+        Ok(power::clear_local_storage())
+    });
+    function(tera, "clear_session_storage", |_| {
+        Ok(power::clear_session_storage())
+    });
+    function(tera, "clear_storage", |args| {
+        Ok(power::clear_storage(&string(args, "storage_type")?))
+    });
+    function(tera, "console_log", |args| {
+        Ok(power::console_log(
+            &string(args, "level")?,
+            &string(args, "message")?,
+        ))
+    });
+    function(tera, "console_table", |args| {
+        Ok(power::console_table(
+            &string(args, "data")?,
+            &string(args, "columns")?,
+        ))
+    });
+    function(tera, "dispatch_event", |args| {
+        Ok(power::dispatch_event(
+            &string(args, "targets")?,
+            &string(args, "name")?,
+            optional(args, "detail")?.unwrap_or_default(),
+        ))
+    });
+    function(tera, "graft", |args| {
+        Ok(power::graft(
+            &string(args, "targets")?,
+            &string(args, "parent")?,
+        ))
+    });
+    function(tera, "history_back", |_| Ok(power::history_back()));
+    function(tera, "history_forward", |_| Ok(power::history_forward()));
+    function(tera, "history_go", |args| {
+        let delta = required(args, "delta")?
+            .as_u64()
+            .ok_or_else(|| Error::msg("`delta` must be a positive integer"))?;
+        Ok(power::history_go(delta as usize))
+    });
+    function(tera, "inner_html", |args| {
+        Ok(power::inner_html(
+            &string(args, "targets")?,
+            string(args, "html")?,
+        ))
+    });
+    function(tera, "insert_adjacent_html", |args| {
+        Ok(power::insert_adjacent_html(
+            &string(args, "targets")?,
+            &string(args, "position")?,
+            string(args, "html")?,
+        ))
+    });
+    function(tera, "insert_adjacent_text", |args| {
+        Ok(power::insert_adjacent_text(
+            &string(args, "targets")?,
+            &string(args, "position")?,
+            &string(args, "text")?,
+        ))
+    });
+    function(tera, "morph", |args| {
+        Ok(power::morph(
+            &string(args, "targets")?,
+            string(args, "html")?,
+        ))
+    });
+    function(tera, "notification", |args| {
+        let title = string(args, "title")?;
+        let body = optional(args, "body")?.unwrap_or_default();
+        let options = args
+            .keys()
+            .filter(|key| !matches!(key.as_str(), "title" | "body"))
+            .map(|key| Ok((key.as_str(), string(args, key)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut options: BTreeMap<&str, &str> = options
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        Ok(power::notification(&title, &mut options, body))
+    });
+    function(tera, "outer_html", |args| {
+        Ok(power::outer_html(
+            &string(args, "targets")?,
+            string(args, "html")?,
+        ))
+    });
+    function(tera, "push_state", |args| {
+        Ok(power::push_state(
+            &string(args, "url")?,
+            &optional(args, "title")?.unwrap_or_default(),
+            optional(args, "state")?.unwrap_or_default(),
+        ))
+    });
+    function(tera, "redirect_to", |args| {
+        Ok(power::redirect_to(
+            &string(args, "url")?,
+            &optional(args, "turbo_action")?.unwrap_or_else(|| "advance".to_string()),
+            optional(args, "turbo_frame")?.as_deref(),
+        ))
+    });
+    function(tera, "reload", |_| Ok(power::reload()));
+    function(tera, "remove_attribute", |args| {
+        Ok(power::remove_attribute(
+            &string(args, "targets")?,
+            &string(args, "attribute")?,
+        ))
+    });
+    function(tera, "remove_css_class", |args| {
+        Ok(power::remove_css_class(
+            &string(args, "targets")?,
+            &string(args, "classes")?,
+        ))
+    });
+    function(tera, "remove_local_storage_item", |args| {
+        Ok(power::remove_local_storage_item(&string(args, "key")?))
+    });
+    function(tera, "remove_session_storage_item", |args| {
+        Ok(power::remove_session_storage_item(&string(args, "key")?))
+    });
+    function(tera, "remove_storage_item", |args| {
+        Ok(power::remove_storage_item(
+            &string(args, "key")?,
+            &string(args, "storage_type")?,
+        ))
+    });
+    function(tera, "replace_state", |args| {
+        Ok(power::replace_state(
+            &string(args, "url")?,
+            &optional(args, "title")?.unwrap_or_default(),
+            optional(args, "state")?.unwrap_or_default(),
+        ))
+    });
+    function(tera, "reset_form", |args| {
+        Ok(power::reset_form(&string(args, "targets")?))
+    });
+    function(tera, "scroll_into_view", |args| {
+        Ok(power::scroll_into_view(&string(args, "targets")?))
+    });
+    function(tera, "set_attribute", |args| {
+        Ok(power::set_attribute(
+            &string(args, "targets")?,
+            &string(args, "attribute")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_cookie", |args| {
+        Ok(power::set_cookie(&string(args, "cookie")?))
+    });
+    function(tera, "set_cookie_item", |args| {
+        Ok(power::set_cookie_item(
+            &string(args, "key")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_dataset_attribute", |args| {
+        Ok(power::set_dataset_attribute(
+            &string(args, "targets")?,
+            &string(args, "attribute")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_focus", |args| {
+        Ok(power::set_focus(&string(args, "targets")?))
+    });
+    function(tera, "set_storage_local_item", |args| {
+        Ok(power::set_storage_local_item(
+            &string(args, "key")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_meta", |args| {
+        Ok(power::set_meta((
+            string(args, "name")?.as_str(),
+            string(args, "content")?.as_str(),
+        )))
+    });
+    function(tera, "set_property", |args| {
+        Ok(power::set_property(
+            &string(args, "targets")?,
+            &string(args, "name")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_storage_session_item", |args| {
+        Ok(power::set_storage_session_item(
+            &string(args, "key")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_storage_item", |args| {
+        Ok(power::set_storage_item(
+            &string(args, "key")?,
+            &string(args, "value")?,
+            &string(args, "storage_type")?,
+        ))
+    });
+    function(tera, "set_style", |args| {
+        Ok(power::set_style(
+            &string(args, "targets")?,
+            &string(args, "name")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "set_styles", |args| {
+        Ok(power::set_styles(
+            &string(args, "targets")?,
+            &string(args, "styles")?,
+        ))
+    });
+    function(tera, "set_title", |args| {
+        Ok(power::set_title(&string(args, "title")?))
+    });
+    function(tera, "set_value", |args| {
+        Ok(power::set_value(
+            &string(args, "targets")?,
+            &string(args, "value")?,
+        ))
+    });
+    function(tera, "text_content", |args| {
+        Ok(power::text_content(
+            &string(args, "targets")?,
+            &string(args, "text")?,
+        ))
+    });
+    #[cfg(feature = "turbo-power-0-6")]
+    function(tera, "toggle_attribute", |args| {
+        Ok(power::toggle_attribute(
+            &string(args, "targets")?,
+            &string(args, "attribute")?,
+            args.get("force").and_then(Value::as_bool),
+        ))
+    });
+    function(
+        tera,
+        "turbo_clear_cache",
+        |_| Ok(power::turbo_clear_cache()),
+    );
+    function(tera, "turbo_frame_reload", |args| {
+        Ok(power::turbo_frame_reload(&string(args, "target")?))
+    });
+    function(tera, "turbo_frame_set_src", |args| {
+        Ok(power::turbo_frame_set_src(
+            &string(args, "target")?,
+            &string(args, "src")?,
+        ))
+    });
+    function(tera, "turbo_progress_bar_hide", |_| {
+        Ok(power::turbo_progress_bar_hide())
+    });
+    function(tera, "turbo_progress_bar_set_value", |args| {
+        Ok(power::turbo_progress_bar_set_value(&string(args, "value")?))
+    });
+    function(tera, "turbo_progress_bar_show", |_| {
+        Ok(power::turbo_progress_bar_show())
+    });
+}
+
+fn function<F>(tera: &mut Tera, action: &str, render: F)
+where
+    F: Fn(&Args) -> Result<String> + Sync + Send + 'static,
+{
+    tera.register_function(&format!("turbo_stream_{action}"), StreamFunction(render));
+}
+
+struct StreamFunction<F>(F);
+
+impl<F> Function for StreamFunction<F>
+where
+    F: Fn(&Args) -> Result<String> + Sync + Send,
+{
+    fn call(&self, args: &Args) -> Result<Value> {
+        (self.0)(args).map(Value::String)
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+/// `{{ record | dom_id(model="clearance", prefix="edit") }}`
+///
+/// The value is either the record id or an object with an `id` field. A missing or `null` id
+/// renders the id of a new record.
+struct DomId;
+
+impl Filter for DomId {
+    fn filter(&self, value: &Value, args: &Args) -> Result<Value> {
+        let model = string(args, "model")?;
+        let prefix = optional(args, "prefix")?;
+        let id = match value {
+            Value::Object(record) => record.get("id").unwrap_or(&Value::Null),
+            value => value,
+        };
+        let id = match id {
+            Value::Null => None,
+            Value::String(id) => Some(id.clone()),
+            id => Some(id.to_string()),
+        };
+        let dom_id = crate::dom_id(&model, id, prefix.as_deref());
+        Ok(Value::String(escape_attribute(&dom_id).into_owned()))
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+fn required<'a>(args: &'a Args, name: &str) -> Result<&'a Value> {
+    args.get(name)
+        .ok_or_else(|| Error::msg(format!("missing required argument `{name}`")))
+}
+
+fn string(args: &Args, name: &str) -> Result<String> {
+    required(args, name).map(to_string)
+}
+
+fn optional(args: &Args, name: &str) -> Result<Option<String>> {
+    Ok(args.get(name).map(to_string))
+}
+
+fn content(args: &Args) -> Result<String> {
+    Ok(optional(args, "content")?.unwrap_or_default())
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::tera::{Context, Tera};
+    use pretty_assertions::assert_eq;
+
+    fn render(template: &str, context: &Context) -> String {
+        let mut tera = Tera::default();
+        super::register(&mut tera);
+        tera.add_raw_template("test", template).unwrap();
+        tera.render("test", context).unwrap()
+    }
+
+    #[test]
+    fn append() {
+        let mut context = Context::new();
+        context.insert("html", "<p>Hello</p>");
+        let expected = r#"<turbo-stream action="append" target="messages"><template><p>Hello</p></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            render(
+                r#"{{ turbo_stream_append(target="messages", content=html) }}"#,
+                &context
+            )
+        );
+    }
+
+    #[test]
+    fn remove_escapes_target() {
+        let expected = r#"<turbo-stream action="remove" target="&quot;message&quot;"><template></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            render(
+                r#"{{ turbo_stream_remove(target='"message"') }}"#,
+                &Context::new()
+            )
+        );
+    }
+
+    #[test]
+    fn set_focus() {
+        let expected = r##"<turbo-stream action="set_focus" targets="#input"><template></template></turbo-stream>"##;
+        assert_eq!(
+            expected,
+            render(
+                r##"{{ turbo_stream_set_focus(targets="#input") }}"##,
+                &Context::new()
+            )
+        );
+    }
+
+    #[test]
+    fn dispatch_event_serializes_detail() {
+        let expected = r##"<turbo-stream action="dispatch_event" name="custom-event" targets="#element"><template>{"foo":"bar"}</template></turbo-stream>"##;
+        let mut context = Context::new();
+        context.insert(
+            "detail",
+            &std::collections::BTreeMap::from([("foo", "bar")]),
+        );
+        assert_eq!(
+            expected,
+            render(
+                r##"{{ turbo_stream_dispatch_event(targets="#element", name="custom-event", detail=detail) }}"##,
+                &context
+            )
+        );
+    }
+
+    #[test]
+    fn history_go() {
+        let expected =
+            r#"<turbo-stream action="history_go" delta="2"><template></template></turbo-stream>"#;
+        assert_eq!(
+            expected,
+            render("{{ turbo_stream_history_go(delta=2) }}", &Context::new())
+        );
+    }
+
+    #[test]
+    fn missing_argument() {
+        let mut tera = Tera::default();
+        super::register(&mut tera);
+        tera.add_raw_template("test", "{{ turbo_stream_remove() }}")
+            .unwrap();
+        assert!(tera.render("test", &Context::new()).is_err());
+    }
+
+    #[test]
+    fn invoke() {
+        let expected = r##"<turbo-stream action="invoke" target="DOM"><template>{"args":[{"behavior":"smooth"}],"method":"scrollIntoView","selector":"#dialog"}</template></turbo-stream>"##;
+        let mut context = Context::new();
+        context.insert(
+            "options",
+            &std::collections::BTreeMap::from([("behavior", "smooth")]),
+        );
+        assert_eq!(
+            expected,
+            render(
+                r##"{{ turbo_stream_invoke(targets="#dialog", method="scrollIntoView", args=[options]) }}"##,
+                &context
+            )
+        );
+    }
+
+    #[test]
+    fn invoke_args_not_an_array() {
+        let mut tera = Tera::default();
+        super::register(&mut tera);
+        tera.add_raw_template(
+            "test",
+            r##"{{ turbo_stream_invoke(targets="#dialog", method="focus", args="x") }}"##,
+        )
+        .unwrap();
+        assert!(tera.render("test", &Context::new()).is_err());
+    }
+
+    #[test]
+    fn dom_id() {
+        let mut context = Context::new();
+        context.insert("persisted", &std::collections::BTreeMap::from([("id", 5)]));
+        context.insert("new", &std::collections::BTreeMap::from([("id", ())]));
+        assert_eq!(
+            "clearance_5 edit_clearance_5 new_clearance",
+            render(
+                r#"{{ persisted | dom_id(model="clearance") }} {{ persisted.id | dom_id(model="clearance", prefix="edit") }} {{ new | dom_id(model="clearance") }}"#,
+                &context
+            )
+        );
+    }
+}